@@ -0,0 +1,540 @@
+use crate::{
+    ast::{
+        Argument, EnumVariant, Expression, IdentifierAST, Literal, MethodSignature, Module,
+        Pattern, Statement, StatementsBlock, TypeAST, TypeParameter, VarKind, Visibility,
+    },
+    token::{Location, Token},
+};
+use alloc::{string::String, vec, vec::Vec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExprId(usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaModule {
+    pub expressions: Vec<ArenaExpression>,
+    pub statements: Vec<ArenaStatement>,
+}
+
+impl ArenaModule {
+    #[inline]
+    #[must_use]
+    pub fn expression(&self, id: ExprId) -> &ArenaExpression {
+        &self.expressions[id.0]
+    }
+}
+
+impl From<&Module> for ArenaModule {
+    fn from(module: &Module) -> Self {
+        let mut lowering = Lowering {
+            expressions: vec![],
+        };
+
+        let statements = module
+            .statements
+            .iter()
+            .map(|statement| lowering.statement(statement))
+            .collect();
+
+        Self {
+            expressions: lowering.expressions,
+            statements,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaStatementsBlock {
+    pub statements: Vec<ArenaStatement>,
+    pub tail: Option<ExprId>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArenaStatement {
+    Expression {
+        location: Location,
+        expression: ExprId,
+    },
+    Return {
+        location: Location,
+        return_value: Option<ExprId>,
+    },
+    Break {
+        location: Location,
+        value: Option<ExprId>,
+    },
+    Continue {
+        location: Location,
+    },
+    Var {
+        location: Location,
+        doc: Option<String>,
+        visibility: Visibility,
+        kind: VarKind,
+        pattern: Pattern,
+        ty: Option<TypeAST>,
+        value: ExprId,
+    },
+    Empty {
+        location: Location,
+    },
+    DoWhile {
+        location: Location,
+        body: ArenaStatementsBlock,
+        condition: ExprId,
+    },
+    Switch {
+        location: Location,
+        scrutinee: ExprId,
+        cases: Vec<ArenaSwitchCase>,
+        default: Option<ArenaStatementsBlock>,
+    },
+    Enum {
+        location: Location,
+        doc: Option<String>,
+        visibility: Visibility,
+        name: IdentifierAST,
+        variants: Vec<EnumVariant>,
+    },
+    Trait {
+        location: Location,
+        doc: Option<String>,
+        visibility: Visibility,
+        name: IdentifierAST,
+        methods: Vec<MethodSignature>,
+    },
+    Yield {
+        location: Location,
+        value: ExprId,
+    },
+    Assert {
+        location: Location,
+        condition: ExprId,
+        message: Option<ExprId>,
+    },
+    Defer {
+        location: Location,
+        expression: ExprId,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaSwitchCase {
+    pub value: ExprId,
+    pub block: ArenaStatementsBlock,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaArgument {
+    pub name: Option<IdentifierAST>,
+    pub value: ExprId,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArenaExpression {
+    Literal(Literal),
+    Binary {
+        left: ExprId,
+        right: ExprId,
+        operator: Token,
+        location: Location,
+    },
+    Postfix {
+        left: ExprId,
+        operator: Token,
+        location: Location,
+    },
+    Prefix {
+        operator: Token,
+        right: ExprId,
+        location: Location,
+    },
+    Identifier(IdentifierAST),
+    Path {
+        segments: Vec<IdentifierAST>,
+        location: Location,
+    },
+    ForceUnwrap {
+        expression: ExprId,
+        location: Location,
+    },
+    Index {
+        left: ExprId,
+        index: ExprId,
+        location: Location,
+    },
+    Assignment {
+        target: ExprId,
+        operator: Token,
+        value: ExprId,
+        location: Location,
+    },
+    Call {
+        callee: ExprId,
+        arguments: Vec<ArenaArgument>,
+        location: Location,
+    },
+    New {
+        class: Vec<IdentifierAST>,
+        arguments: Vec<ArenaArgument>,
+        location: Location,
+    },
+    FieldAccess {
+        left: ExprId,
+        right: IdentifierAST,
+        location: Location,
+    },
+    OptionalFieldAccess {
+        left: ExprId,
+        right: IdentifierAST,
+        location: Location,
+    },
+    OptionalIndex {
+        left: ExprId,
+        index: ExprId,
+        location: Location,
+    },
+    Grouping {
+        inner: ExprId,
+        location: Location,
+    },
+    Function {
+        type_parameters: Vec<TypeParameter>,
+        parameters: Vec<IdentifierAST>,
+        block: ArenaStatementsBlock,
+        location: Location,
+    },
+    Comma {
+        expressions: Vec<ExprId>,
+        location: Location,
+    },
+}
+
+struct Lowering {
+    expressions: Vec<ArenaExpression>,
+}
+
+impl Lowering {
+    fn push(&mut self, expression: ArenaExpression) -> ExprId {
+        self.expressions.push(expression);
+        ExprId(self.expressions.len() - 1)
+    }
+
+    fn statements_block(&mut self, block: &StatementsBlock) -> ArenaStatementsBlock {
+        ArenaStatementsBlock {
+            statements: block
+                .statements
+                .iter()
+                .map(|statement| self.statement(statement))
+                .collect(),
+            tail: block.tail.as_ref().map(|tail| self.expression(tail)),
+            location: block.location,
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[Argument]) -> Vec<ArenaArgument> {
+        arguments
+            .iter()
+            .map(|argument| ArenaArgument {
+                name: argument.name.clone(),
+                value: self.expression(&argument.value),
+            })
+            .collect()
+    }
+
+    fn statement(&mut self, statement: &Statement) -> ArenaStatement {
+        match statement {
+            Statement::Expression {
+                location,
+                expression,
+            } => ArenaStatement::Expression {
+                location: *location,
+                expression: self.expression(expression),
+            },
+            Statement::Return {
+                location,
+                return_value,
+            } => ArenaStatement::Return {
+                location: *location,
+                return_value: return_value.as_ref().map(|value| self.expression(value)),
+            },
+            Statement::Break { location, value } => ArenaStatement::Break {
+                location: *location,
+                value: value.as_ref().map(|value| self.expression(value)),
+            },
+            Statement::Continue { location } => ArenaStatement::Continue {
+                location: *location,
+            },
+            Statement::Var {
+                location,
+                doc,
+                visibility,
+                kind,
+                pattern,
+                ty,
+                value,
+            } => ArenaStatement::Var {
+                location: *location,
+                doc: doc.clone(),
+                visibility: *visibility,
+                kind: *kind,
+                pattern: pattern.clone(),
+                ty: ty.clone(),
+                value: self.expression(value),
+            },
+            Statement::Empty { location } => ArenaStatement::Empty {
+                location: *location,
+            },
+            Statement::DoWhile {
+                location,
+                body,
+                condition,
+            } => ArenaStatement::DoWhile {
+                location: *location,
+                body: self.statements_block(body),
+                condition: self.expression(condition),
+            },
+            Statement::Switch {
+                location,
+                scrutinee,
+                cases,
+                default,
+            } => ArenaStatement::Switch {
+                location: *location,
+                scrutinee: self.expression(scrutinee),
+                cases: cases
+                    .iter()
+                    .map(|case| ArenaSwitchCase {
+                        value: self.expression(&case.value),
+                        block: self.statements_block(&case.block),
+                        location: case.location,
+                    })
+                    .collect(),
+                default: default.as_ref().map(|block| self.statements_block(block)),
+            },
+            Statement::Enum {
+                location,
+                doc,
+                visibility,
+                name,
+                variants,
+            } => ArenaStatement::Enum {
+                location: *location,
+                doc: doc.clone(),
+                visibility: *visibility,
+                name: name.clone(),
+                variants: variants.clone(),
+            },
+            Statement::Trait {
+                location,
+                doc,
+                visibility,
+                name,
+                methods,
+            } => ArenaStatement::Trait {
+                location: *location,
+                doc: doc.clone(),
+                visibility: *visibility,
+                name: name.clone(),
+                methods: methods.clone(),
+            },
+            Statement::Yield { location, value } => ArenaStatement::Yield {
+                location: *location,
+                value: self.expression(value),
+            },
+            Statement::Defer {
+                location,
+                expression,
+            } => ArenaStatement::Defer {
+                location: *location,
+                expression: self.expression(expression),
+            },
+            Statement::Assert {
+                location,
+                condition,
+                message,
+            } => ArenaStatement::Assert {
+                location: *location,
+                condition: self.expression(condition),
+                message: message.as_ref().map(|message| self.expression(message)),
+            },
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) -> ExprId {
+        let lowered = match expression {
+            Expression::Literal(literal) => ArenaExpression::Literal(literal.clone()),
+            Expression::Binary {
+                left,
+                right,
+                operator,
+                location,
+            } => ArenaExpression::Binary {
+                left: self.expression(left),
+                right: self.expression(right),
+                operator: operator.clone(),
+                location: *location,
+            },
+            Expression::Postfix {
+                left,
+                operator,
+                location,
+            } => ArenaExpression::Postfix {
+                left: self.expression(left),
+                operator: operator.clone(),
+                location: *location,
+            },
+            Expression::Prefix {
+                operator,
+                right,
+                location,
+            } => ArenaExpression::Prefix {
+                operator: operator.clone(),
+                right: self.expression(right),
+                location: *location,
+            },
+            Expression::Identifier(identifier) => ArenaExpression::Identifier(identifier.clone()),
+            Expression::Path { segments, location } => ArenaExpression::Path {
+                segments: segments.clone(),
+                location: *location,
+            },
+            Expression::ForceUnwrap {
+                expression,
+                location,
+            } => ArenaExpression::ForceUnwrap {
+                expression: self.expression(expression),
+                location: *location,
+            },
+            Expression::Index {
+                left,
+                index,
+                location,
+            } => ArenaExpression::Index {
+                left: self.expression(left),
+                index: self.expression(index),
+                location: *location,
+            },
+            Expression::Assignment {
+                target,
+                operator,
+                value,
+                location,
+            } => ArenaExpression::Assignment {
+                target: self.expression(target),
+                operator: operator.clone(),
+                value: self.expression(value),
+                location: *location,
+            },
+            Expression::Call {
+                callee,
+                arguments,
+                location,
+            } => ArenaExpression::Call {
+                callee: self.expression(callee),
+                arguments: self.arguments(arguments),
+                location: *location,
+            },
+            Expression::New {
+                class,
+                arguments,
+                location,
+            } => ArenaExpression::New {
+                class: class.clone(),
+                arguments: self.arguments(arguments),
+                location: *location,
+            },
+            Expression::FieldAccess {
+                left,
+                right,
+                location,
+            } => ArenaExpression::FieldAccess {
+                left: self.expression(left),
+                right: right.clone(),
+                location: *location,
+            },
+            Expression::OptionalFieldAccess {
+                left,
+                right,
+                location,
+            } => ArenaExpression::OptionalFieldAccess {
+                left: self.expression(left),
+                right: right.clone(),
+                location: *location,
+            },
+            Expression::OptionalIndex {
+                left,
+                index,
+                location,
+            } => ArenaExpression::OptionalIndex {
+                left: self.expression(left),
+                index: self.expression(index),
+                location: *location,
+            },
+            Expression::Grouping { inner, location } => ArenaExpression::Grouping {
+                inner: self.expression(inner),
+                location: *location,
+            },
+            Expression::Function {
+                type_parameters,
+                parameters,
+                block,
+                location,
+            } => ArenaExpression::Function {
+                type_parameters: type_parameters.clone(),
+                parameters: parameters.clone(),
+                block: self.statements_block(block),
+                location: *location,
+            },
+            Expression::Comma {
+                expressions,
+                location,
+            } => ArenaExpression::Comma {
+                expressions: expressions
+                    .iter()
+                    .map(|expression| self.expression(expression))
+                    .collect(),
+                location: *location,
+            },
+        };
+
+        self.push(lowered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parser::Parser, token::Location};
+
+    use super::{ArenaExpression, ArenaModule, ArenaStatement};
+
+    #[test]
+    fn lowering() {
+        let module = Parser::new("a + b;").parse().unwrap();
+        let arena = ArenaModule::from(&module);
+
+        let [ArenaStatement::Expression { expression, .. }] = arena.statements.as_slice() else {
+            panic!("expected a single expression statement");
+        };
+        let ArenaExpression::Binary {
+            left,
+            right,
+            location,
+            ..
+        } = arena.expression(*expression)
+        else {
+            panic!("expected a binary expression");
+        };
+
+        assert_eq!(*location, Location { start: 0, end: 5 });
+        assert!(matches!(
+            arena.expression(*left),
+            ArenaExpression::Identifier(identifier) if identifier.identifier == "a"
+        ));
+        assert!(matches!(
+            arena.expression(*right),
+            ArenaExpression::Identifier(identifier) if identifier.identifier == "b"
+        ));
+    }
+}
@@ -1,14 +1,28 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt::{self, Display};
+
 use crate::token::{Location, Token};
 
-pub type Module = Vec<Statement>;
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
+pub struct Module {
+    /// Leading `//!` lines, joined with newlines.
+    pub doc: Option<String>,
+    pub statements: Vec<Statement>,
+}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
 pub struct StatementsBlock {
     pub statements: Vec<Statement>,
+    /// A final expression without a `;`, which is the value of the block.
+    /// Only function bodies have one: `fun (a, b) { a + b }`.
+    pub tail: Option<Box<Expression>>,
     pub location: Location,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
 pub enum Statement {
     Expression {
         location: Location,
@@ -16,22 +30,138 @@ pub enum Statement {
     },
     Return {
         location: Location,
-        return_value: Expression,
+        return_value: Option<Expression>,
     },
     Break {
         location: Location,
+        value: Option<Expression>,
     },
     Continue {
         location: Location,
     },
     Var {
         location: Location,
+        doc: Option<String>,
+        visibility: Visibility,
+        kind: VarKind,
+        pattern: Pattern,
+        ty: Option<TypeAST>,
+        value: Expression,
+    },
+    Empty {
+        location: Location,
+    },
+    DoWhile {
+        location: Location,
+        body: StatementsBlock,
+        condition: Expression,
+    },
+    Switch {
+        location: Location,
+        scrutinee: Expression,
+        cases: Vec<SwitchCase>,
+        default: Option<StatementsBlock>,
+    },
+    Enum {
+        location: Location,
+        doc: Option<String>,
+        visibility: Visibility,
         name: IdentifierAST,
+        variants: Vec<EnumVariant>,
+    },
+    Yield {
+        location: Location,
         value: Expression,
     },
+    Assert {
+        location: Location,
+        condition: Expression,
+        message: Option<Expression>,
+    },
+    Trait {
+        location: Location,
+        doc: Option<String>,
+        visibility: Visibility,
+        name: IdentifierAST,
+        methods: Vec<MethodSignature>,
+    },
+    /// `defer expression;`, run when the enclosing block exits, most
+    /// recently deferred first.
+    Defer {
+        location: Location,
+        expression: Expression,
+    },
 }
 
+impl Statement {
+    /// The variant, without its fields.
+    #[must_use]
+    pub const fn kind(&self) -> StatementKind {
+        match self {
+            Self::Expression { .. } => StatementKind::Expression,
+            Self::Return { .. } => StatementKind::Return,
+            Self::Break { .. } => StatementKind::Break,
+            Self::Continue { .. } => StatementKind::Continue,
+            Self::Var { .. } => StatementKind::Var,
+            Self::Empty { .. } => StatementKind::Empty,
+            Self::DoWhile { .. } => StatementKind::DoWhile,
+            Self::Switch { .. } => StatementKind::Switch,
+            Self::Enum { .. } => StatementKind::Enum,
+            Self::Yield { .. } => StatementKind::Yield,
+            Self::Assert { .. } => StatementKind::Assert,
+            Self::Trait { .. } => StatementKind::Trait,
+            Self::Defer { .. } => StatementKind::Defer,
+        }
+    }
+}
+
+/// Which [`Statement`] variant a statement is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatementKind {
+    Expression,
+    Return,
+    Break,
+    Continue,
+    Var,
+    Empty,
+    DoWhile,
+    Switch,
+    Enum,
+    Yield,
+    Assert,
+    Trait,
+    Defer,
+}
+
+/// `Name` or `Name(field, ...)` inside an `enum` declaration.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
+pub struct EnumVariant {
+    pub name: IdentifierAST,
+    pub fields: Vec<IdentifierAST>,
+    pub location: Location,
+}
+
+/// `fun name(a, b);` inside a `trait` declaration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
+pub struct MethodSignature {
+    pub name: IdentifierAST,
+    pub parameters: Vec<IdentifierAST>,
+    pub location: Location,
+}
+
+/// A single `case value { ... }` arm. Arms never fall through into each other.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
+pub struct SwitchCase {
+    pub value: Expression,
+    pub block: StatementsBlock,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
 pub enum Expression {
     // 2
     Literal(Literal),
@@ -56,10 +186,39 @@ pub enum Expression {
     },
     // a
     Identifier(IdentifierAST),
-    // a()
+    // a::b::c
+    Path {
+        segments: Vec<IdentifierAST>,
+        location: Location,
+    },
+    // a!
+    ForceUnwrap {
+        expression: Box<Expression>,
+        location: Location,
+    },
+    // a[b]
+    Index {
+        left: Box<Expression>,
+        index: Box<Expression>,
+        location: Location,
+    },
+    // a.b = c, a += b
+    Assignment {
+        target: Box<Expression>,
+        operator: Token,
+        value: Box<Expression>,
+        location: Location,
+    },
+    // a(b, c: d)
     Call {
         callee: Box<Expression>,
-        arguments: Vec<Expression>,
+        arguments: Vec<Argument>,
+        location: Location,
+    },
+    // new a::B(c, d: e)
+    New {
+        class: Vec<IdentifierAST>,
+        arguments: Vec<Argument>,
         location: Location,
     },
     // a.b
@@ -68,12 +227,35 @@ pub enum Expression {
         right: IdentifierAST,
         location: Location,
     },
-    // fun (a, b) { a + b }
+    // a?.b
+    OptionalFieldAccess {
+        left: Box<Expression>,
+        right: IdentifierAST,
+        location: Location,
+    },
+    // a?.[b]
+    OptionalIndex {
+        left: Box<Expression>,
+        index: Box<Expression>,
+        location: Location,
+    },
+    // (a), only kept when parsing with `preserve_parens`
+    Grouping {
+        inner: Box<Expression>,
+        location: Location,
+    },
+    // fun [T] (a, b) { a + b }
     Function {
+        type_parameters: Vec<TypeParameter>,
         parameters: Vec<IdentifierAST>,
         block: StatementsBlock,
         location: Location,
     },
+    // a = 1, b = 2, only parsed with `comma_expressions`
+    Comma {
+        expressions: Vec<Expression>,
+        location: Location,
+    },
 }
 
 impl Expression {
@@ -87,29 +269,480 @@ impl Expression {
             | Self::Binary { location, .. }
             | Self::Literal(Literal { location, .. })
             | Self::Call { location, .. }
+            | Self::New { location, .. }
             | Self::FieldAccess { location, .. }
-            | Self::Function { location, .. } => *location,
+            | Self::Grouping { location, .. }
+            | Self::ForceUnwrap { location, .. }
+            | Self::Index { location, .. }
+            | Self::Assignment { location, .. }
+            | Self::Function { location, .. }
+            | Self::Path { location, .. }
+            | Self::OptionalFieldAccess { location, .. }
+            | Self::OptionalIndex { location, .. }
+            | Self::Comma { location, .. } => *location,
+        }
+    }
+
+    /// The variant, without its fields.
+    #[must_use]
+    pub const fn kind(&self) -> ExpressionKind {
+        match self {
+            Self::Literal(..) => ExpressionKind::Literal,
+            Self::Binary { .. } => ExpressionKind::Binary,
+            Self::Postfix { .. } => ExpressionKind::Postfix,
+            Self::Prefix { .. } => ExpressionKind::Prefix,
+            Self::Identifier(..) => ExpressionKind::Identifier,
+            Self::Path { .. } => ExpressionKind::Path,
+            Self::ForceUnwrap { .. } => ExpressionKind::ForceUnwrap,
+            Self::Index { .. } => ExpressionKind::Index,
+            Self::Assignment { .. } => ExpressionKind::Assignment,
+            Self::Call { .. } => ExpressionKind::Call,
+            Self::New { .. } => ExpressionKind::New,
+            Self::FieldAccess { .. } => ExpressionKind::FieldAccess,
+            Self::OptionalFieldAccess { .. } => ExpressionKind::OptionalFieldAccess,
+            Self::OptionalIndex { .. } => ExpressionKind::OptionalIndex,
+            Self::Grouping { .. } => ExpressionKind::Grouping,
+            Self::Function { .. } => ExpressionKind::Function,
+            Self::Comma { .. } => ExpressionKind::Comma,
+        }
+    }
+
+    /// Whether the expression denotes a place that can be assigned to: a
+    /// variable, or a chain of field accesses and indexing rooted at one.
+    /// Chains through call results (`a.b()[0]`) are rejected.
+    #[must_use]
+    pub fn is_assignable(&self) -> bool {
+        match self {
+            Self::Identifier(..) => true,
+            Self::FieldAccess { left, .. } | Self::Index { left, .. } => left.is_assignable(),
+            Self::Grouping { inner, .. } => inner.is_assignable(),
+            _ => false,
+        }
+    }
+
+    /// Looks through any [`Expression::Grouping`] wrappers.
+    #[must_use]
+    pub fn ungrouped(&self) -> &Expression {
+        match self {
+            Self::Grouping { inner, .. } => inner.ungrouped(),
+            _ => self,
+        }
+    }
+}
+
+/// Which [`Expression`] variant an expression is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExpressionKind {
+    Literal,
+    Binary,
+    Postfix,
+    Prefix,
+    Identifier,
+    Path,
+    ForceUnwrap,
+    Index,
+    Assignment,
+    Call,
+    New,
+    FieldAccess,
+    OptionalFieldAccess,
+    OptionalIndex,
+    Grouping,
+    Function,
+    Comma,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+pub enum Visibility {
+    #[default]
+    Default,
+    Public,
+    Private,
+}
+
+/// Which scope a `var`-like statement binds in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+pub enum VarKind {
+    /// `var x = 1;`, the innermost scope.
+    #[default]
+    Var,
+    /// `global x = 1;`, the module scope, even inside a function.
+    Global,
+    /// `local x = 1;`, the current function's scope, even when an outer
+    /// scope has a binding with the same name.
+    Local,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
+pub enum Pattern {
+    // a
+    Identifier(IdentifierAST),
+    // (a, b)
+    Tuple {
+        elements: Vec<Pattern>,
+        location: Location,
+    },
+    // [a, b]
+    Array {
+        elements: Vec<Pattern>,
+        location: Location,
+    },
+    // ..rest, only as an element of a tuple or array pattern
+    Rest {
+        name: IdentifierAST,
+        location: Location,
+    },
+}
+
+impl Pattern {
+    #[inline]
+    #[must_use]
+    pub const fn location(&self) -> Location {
+        match self {
+            Self::Identifier(IdentifierAST { location, .. })
+            | Self::Tuple { location, .. }
+            | Self::Array { location, .. }
+            | Self::Rest { location, .. } => *location,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
+pub enum TypeAST {
+    // Int
+    Name(IdentifierAST),
+    // (Int, Int)
+    Tuple {
+        elements: Vec<TypeAST>,
+        location: Location,
+    },
+}
+
+impl TypeAST {
+    #[inline]
+    #[must_use]
+    pub const fn location(&self) -> Location {
+        match self {
+            Self::Name(IdentifierAST { location, .. }) | Self::Tuple { location, .. } => *location,
         }
     }
 }
 
+/// `T` or `T: Bound` inside `fun [...]`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
+pub struct TypeParameter {
+    pub name: IdentifierAST,
+    pub bound: Option<TypeAST>,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
+pub struct Argument {
+    pub name: Option<IdentifierAST>,
+    pub value: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
 pub struct Literal {
     pub raw: RawLiteral,
+    /// The literal exactly as written, when that differs from how `raw`
+    /// prints (`007`, `1e10`; only numbers keep it). The formatter prefers
+    /// this over `raw`.
+    pub source_text: Option<String>,
     pub location: Location,
 }
 
+impl Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(source_text) = &self.source_text {
+            return f.write_str(source_text);
+        }
+
+        match &self.raw {
+            RawLiteral::Integer(value) => write!(f, "{}", value),
+            RawLiteral::Float(value) => write!(f, "{:?}", value),
+            RawLiteral::String(value) => write!(f, "{:?}", value),
+            RawLiteral::Bytes(value) => write!(f, "b\"{}\"", value.escape_ascii()),
+            RawLiteral::Char(value) => write!(f, "'{}'", value.escape_debug()),
+            RawLiteral::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
 pub struct IdentifierAST {
     pub identifier: String,
     pub location: Location,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "hash"), derive(PartialEq))]
 pub enum RawLiteral {
     Integer(u64),
     Float(f64),
     String(String),
+    Bytes(Vec<u8>),
     Char(char),
     Bool(bool),
 }
+
+impl RawLiteral {
+    #[must_use]
+    pub const fn as_integer(&self) -> Option<u64> {
+        match self {
+            Self::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_string(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_char(&self) -> Option<char> {
+        match self {
+            Self::Char(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+// See the note on `RawToken`: with `hash`, floats compare by bit pattern.
+#[cfg(feature = "hash")]
+impl PartialEq for RawLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a.to_bits() == b.to_bits(),
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Bytes(a), Self::Bytes(b)) => a == b,
+            (Self::Char(a), Self::Char(b)) => a == b,
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Eq for RawLiteral {}
+
+#[cfg(feature = "hash")]
+impl core::hash::Hash for RawLiteral {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+
+        match self {
+            Self::Integer(value) => value.hash(state),
+            Self::Float(value) => value.to_bits().hash(state),
+            Self::String(value) => value.hash(state),
+            Self::Bytes(value) => value.hash(state),
+            Self::Char(value) => value.hash(state),
+            Self::Bool(value) => value.hash(state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "hash")]
+    use std::collections::HashSet;
+
+    #[cfg(feature = "hash")]
+    use crate::token::{Punctuation, RawToken, Token};
+    use crate::{
+        parser::{Parser, ParserOptions},
+        token::{Location, Precedence},
+    };
+
+    use super::{Expression, ExpressionKind, Literal, RawLiteral, Statement, StatementKind};
+
+    #[test]
+    fn statement_kinds() {
+        let module = Parser::new(
+            "a; return; break; continue; var a = 1; ; do {} while a; switch a {} \
+             enum E {} yield a; assert a; trait T {} defer a;",
+        )
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            module
+                .statements
+                .iter()
+                .map(|statement| statement.kind())
+                .collect::<Vec<_>>(),
+            vec![
+                StatementKind::Expression,
+                StatementKind::Return,
+                StatementKind::Break,
+                StatementKind::Continue,
+                StatementKind::Var,
+                StatementKind::Empty,
+                StatementKind::DoWhile,
+                StatementKind::Switch,
+                StatementKind::Enum,
+                StatementKind::Yield,
+                StatementKind::Assert,
+                StatementKind::Trait,
+                StatementKind::Defer,
+            ]
+        );
+    }
+
+    #[test]
+    fn expression_kinds() {
+        for (source, kind) in [
+            ("1", ExpressionKind::Literal),
+            ("a + b", ExpressionKind::Binary),
+            ("a++", ExpressionKind::Postfix),
+            ("-a", ExpressionKind::Prefix),
+            ("a", ExpressionKind::Identifier),
+            ("a::b", ExpressionKind::Path),
+            ("a!", ExpressionKind::ForceUnwrap),
+            ("a[b]", ExpressionKind::Index),
+            ("a = b", ExpressionKind::Assignment),
+            ("a(b)", ExpressionKind::Call),
+            ("new A()", ExpressionKind::New),
+            ("a.b", ExpressionKind::FieldAccess),
+            ("a?.b", ExpressionKind::OptionalFieldAccess),
+            ("a?.[b]", ExpressionKind::OptionalIndex),
+            ("fun () {}", ExpressionKind::Function),
+        ] {
+            let expression = Parser::new(source)
+                .parse_expression(Precedence::Lowest)
+                .unwrap();
+
+            assert_eq!(expression.kind(), kind, "{source}");
+        }
+
+        let grouping = Expression::Grouping {
+            inner: Box::new(Expression::Literal(Literal {
+                raw: RawLiteral::Integer(1),
+                source_text: None,
+                location: Location { start: 1, end: 2 },
+            })),
+            location: Location { start: 0, end: 3 },
+        };
+        assert_eq!(grouping.kind(), ExpressionKind::Grouping);
+
+        let statement = Parser::new("a, b;")
+            .with_options(ParserOptions {
+                comma_expressions: true,
+                ..ParserOptions::default()
+            })
+            .parse_statement()
+            .unwrap();
+        let Statement::Expression { expression, .. } = statement else {
+            panic!("expected an expression statement, got {statement:?}");
+        };
+        assert_eq!(expression.kind(), ExpressionKind::Comma);
+    }
+
+    #[test]
+    fn literal_accessors() {
+        let integer = RawLiteral::Integer(7);
+        let string = RawLiteral::String("hi".to_owned());
+
+        assert_eq!(integer.as_integer(), Some(7));
+        assert_eq!(RawLiteral::Float(1.5).as_float(), Some(1.5));
+        assert_eq!(string.as_string(), Some("hi"));
+        assert_eq!(RawLiteral::Bytes(vec![0xFF]).as_bytes(), Some(&[0xFF][..]));
+        assert_eq!(RawLiteral::Char('c').as_char(), Some('c'));
+        assert_eq!(RawLiteral::Bool(true).as_bool(), Some(true));
+
+        assert_eq!(string.as_integer(), None);
+        assert_eq!(integer.as_float(), None);
+        assert_eq!(integer.as_string(), None);
+        assert_eq!(string.as_bytes(), None);
+        assert_eq!(string.as_char(), None);
+        assert_eq!(integer.as_bool(), None);
+    }
+
+    #[cfg(feature = "hash")]
+    fn float_sum() -> Expression {
+        Expression::Binary {
+            left: Box::new(Expression::Literal(Literal {
+                raw: RawLiteral::Float(1.5),
+                source_text: None,
+                location: Location { start: 0, end: 3 },
+            })),
+            right: Box::new(Expression::Literal(Literal {
+                raw: RawLiteral::Integer(2),
+                source_text: None,
+                location: Location { start: 6, end: 7 },
+            })),
+            operator: Token {
+                raw: RawToken::Punctuation(Punctuation::Plus),
+                location: Location { start: 4, end: 5 },
+            },
+            location: Location { start: 0, end: 7 },
+        }
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn structurally_equal_expressions_share_a_hash_set_entry() {
+        let mut set = HashSet::new();
+
+        set.insert(float_sum());
+        set.insert(float_sum());
+
+        assert_eq!(set.len(), 1);
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn floats_compare_by_bit_pattern() {
+        assert_ne!(RawLiteral::Float(0.0), RawLiteral::Float(-0.0));
+        assert_eq!(RawLiteral::Float(f64::NAN), RawLiteral::Float(f64::NAN));
+
+        let float = |value: f64| RawToken::FloatLiteral {
+            value,
+            source_text: String::new(),
+        };
+
+        assert_ne!(float(0.0), float(-0.0));
+        assert_eq!(float(f64::NAN), float(f64::NAN));
+        assert_eq!(
+            HashSet::from([float(0.0), float(-0.0), float(f64::NAN), float(f64::NAN)]).len(),
+            3
+        );
+    }
+}
@@ -0,0 +1,459 @@
+//! Structural AST comparison that ignores every [`Location`] field.
+//!
+//! [`Location`]: crate::token::Location
+
+use crate::{
+    ast::{
+        Argument, EnumVariant, Expression, IdentifierAST, MethodSignature, Pattern, Statement,
+        StatementsBlock, SwitchCase, TypeAST, TypeParameter,
+    },
+    token::Token,
+};
+
+/// Whether two expressions have the same shape and payloads, regardless of
+/// where in the source they came from.
+#[must_use]
+pub fn ast_eq_ignoring_locations(a: &Expression, b: &Expression) -> bool {
+    match (a, b) {
+        (Expression::Literal(a), Expression::Literal(b)) => a.raw == b.raw,
+        (
+            Expression::Binary {
+                left: a_left,
+                right: a_right,
+                operator: a_operator,
+                ..
+            },
+            Expression::Binary {
+                left: b_left,
+                right: b_right,
+                operator: b_operator,
+                ..
+            },
+        ) => {
+            token_eq(a_operator, b_operator)
+                && ast_eq_ignoring_locations(a_left, b_left)
+                && ast_eq_ignoring_locations(a_right, b_right)
+        }
+        (
+            Expression::Postfix {
+                left: a_left,
+                operator: a_operator,
+                ..
+            },
+            Expression::Postfix {
+                left: b_left,
+                operator: b_operator,
+                ..
+            },
+        ) => token_eq(a_operator, b_operator) && ast_eq_ignoring_locations(a_left, b_left),
+        (
+            Expression::Prefix {
+                operator: a_operator,
+                right: a_right,
+                ..
+            },
+            Expression::Prefix {
+                operator: b_operator,
+                right: b_right,
+                ..
+            },
+        ) => token_eq(a_operator, b_operator) && ast_eq_ignoring_locations(a_right, b_right),
+        (Expression::Identifier(a), Expression::Identifier(b)) => identifier_eq(a, b),
+        (Expression::Path { segments: a, .. }, Expression::Path { segments: b, .. }) => {
+            slice_eq(a, b, identifier_eq)
+        }
+        (
+            Expression::ForceUnwrap { expression: a, .. },
+            Expression::ForceUnwrap { expression: b, .. },
+        )
+        | (Expression::Grouping { inner: a, .. }, Expression::Grouping { inner: b, .. }) => {
+            ast_eq_ignoring_locations(a, b)
+        }
+        (
+            Expression::Index {
+                left: a_left,
+                index: a_index,
+                ..
+            },
+            Expression::Index {
+                left: b_left,
+                index: b_index,
+                ..
+            },
+        )
+        | (
+            Expression::OptionalIndex {
+                left: a_left,
+                index: a_index,
+                ..
+            },
+            Expression::OptionalIndex {
+                left: b_left,
+                index: b_index,
+                ..
+            },
+        ) => {
+            ast_eq_ignoring_locations(a_left, b_left) && ast_eq_ignoring_locations(a_index, b_index)
+        }
+        (
+            Expression::Assignment {
+                target: a_target,
+                operator: a_operator,
+                value: a_value,
+                ..
+            },
+            Expression::Assignment {
+                target: b_target,
+                operator: b_operator,
+                value: b_value,
+                ..
+            },
+        ) => {
+            token_eq(a_operator, b_operator)
+                && ast_eq_ignoring_locations(a_target, b_target)
+                && ast_eq_ignoring_locations(a_value, b_value)
+        }
+        (
+            Expression::Call {
+                callee: a_callee,
+                arguments: a_arguments,
+                ..
+            },
+            Expression::Call {
+                callee: b_callee,
+                arguments: b_arguments,
+                ..
+            },
+        ) => {
+            ast_eq_ignoring_locations(a_callee, b_callee)
+                && slice_eq(a_arguments, b_arguments, argument_eq)
+        }
+        (
+            Expression::New {
+                class: a_class,
+                arguments: a_arguments,
+                ..
+            },
+            Expression::New {
+                class: b_class,
+                arguments: b_arguments,
+                ..
+            },
+        ) => {
+            slice_eq(a_class, b_class, identifier_eq)
+                && slice_eq(a_arguments, b_arguments, argument_eq)
+        }
+        (
+            Expression::FieldAccess {
+                left: a_left,
+                right: a_right,
+                ..
+            },
+            Expression::FieldAccess {
+                left: b_left,
+                right: b_right,
+                ..
+            },
+        )
+        | (
+            Expression::OptionalFieldAccess {
+                left: a_left,
+                right: a_right,
+                ..
+            },
+            Expression::OptionalFieldAccess {
+                left: b_left,
+                right: b_right,
+                ..
+            },
+        ) => identifier_eq(a_right, b_right) && ast_eq_ignoring_locations(a_left, b_left),
+        (
+            Expression::Function {
+                type_parameters: a_type_parameters,
+                parameters: a_parameters,
+                block: a_block,
+                ..
+            },
+            Expression::Function {
+                type_parameters: b_type_parameters,
+                parameters: b_parameters,
+                block: b_block,
+                ..
+            },
+        ) => {
+            slice_eq(a_type_parameters, b_type_parameters, type_parameter_eq)
+                && slice_eq(a_parameters, b_parameters, identifier_eq)
+                && block_eq(a_block, b_block)
+        }
+        (Expression::Comma { expressions: a, .. }, Expression::Comma { expressions: b, .. }) => {
+            slice_eq(a, b, ast_eq_ignoring_locations)
+        }
+        _ => false,
+    }
+}
+
+/// [`ast_eq_ignoring_locations`] for statements.
+#[must_use]
+pub fn statement_eq_ignoring_locations(a: &Statement, b: &Statement) -> bool {
+    match (a, b) {
+        (
+            Statement::Expression { expression: a, .. },
+            Statement::Expression { expression: b, .. },
+        ) => ast_eq_ignoring_locations(a, b),
+        (Statement::Yield { value: a, .. }, Statement::Yield { value: b, .. })
+        | (Statement::Defer { expression: a, .. }, Statement::Defer { expression: b, .. }) => {
+            ast_eq_ignoring_locations(a, b)
+        }
+        (
+            Statement::Assert {
+                condition: a_condition,
+                message: a_message,
+                ..
+            },
+            Statement::Assert {
+                condition: b_condition,
+                message: b_message,
+                ..
+            },
+        ) => {
+            ast_eq_ignoring_locations(a_condition, b_condition)
+                && option_eq(
+                    a_message.as_ref(),
+                    b_message.as_ref(),
+                    ast_eq_ignoring_locations,
+                )
+        }
+        (
+            Statement::Return {
+                return_value: a, ..
+            },
+            Statement::Return {
+                return_value: b, ..
+            },
+        )
+        | (Statement::Break { value: a, .. }, Statement::Break { value: b, .. }) => {
+            option_eq(a.as_ref(), b.as_ref(), ast_eq_ignoring_locations)
+        }
+        (Statement::Continue { .. }, Statement::Continue { .. })
+        | (Statement::Empty { .. }, Statement::Empty { .. }) => true,
+        (
+            Statement::Var {
+                doc: a_doc,
+                visibility: a_visibility,
+                kind: a_kind,
+                pattern: a_pattern,
+                ty: a_ty,
+                value: a_value,
+                ..
+            },
+            Statement::Var {
+                doc: b_doc,
+                visibility: b_visibility,
+                kind: b_kind,
+                pattern: b_pattern,
+                ty: b_ty,
+                value: b_value,
+                ..
+            },
+        ) => {
+            a_doc == b_doc
+                && a_visibility == b_visibility
+                && a_kind == b_kind
+                && pattern_eq(a_pattern, b_pattern)
+                && option_eq(a_ty.as_ref(), b_ty.as_ref(), type_eq)
+                && ast_eq_ignoring_locations(a_value, b_value)
+        }
+        (
+            Statement::DoWhile {
+                body: a_body,
+                condition: a_condition,
+                ..
+            },
+            Statement::DoWhile {
+                body: b_body,
+                condition: b_condition,
+                ..
+            },
+        ) => block_eq(a_body, b_body) && ast_eq_ignoring_locations(a_condition, b_condition),
+        (
+            Statement::Switch {
+                scrutinee: a_scrutinee,
+                cases: a_cases,
+                default: a_default,
+                ..
+            },
+            Statement::Switch {
+                scrutinee: b_scrutinee,
+                cases: b_cases,
+                default: b_default,
+                ..
+            },
+        ) => {
+            ast_eq_ignoring_locations(a_scrutinee, b_scrutinee)
+                && slice_eq(a_cases, b_cases, switch_case_eq)
+                && option_eq(a_default.as_ref(), b_default.as_ref(), block_eq)
+        }
+        (
+            Statement::Enum {
+                doc: a_doc,
+                visibility: a_visibility,
+                name: a_name,
+                variants: a_variants,
+                ..
+            },
+            Statement::Enum {
+                doc: b_doc,
+                visibility: b_visibility,
+                name: b_name,
+                variants: b_variants,
+                ..
+            },
+        ) => {
+            a_doc == b_doc
+                && a_visibility == b_visibility
+                && identifier_eq(a_name, b_name)
+                && slice_eq(a_variants, b_variants, enum_variant_eq)
+        }
+        (
+            Statement::Trait {
+                doc: a_doc,
+                visibility: a_visibility,
+                name: a_name,
+                methods: a_methods,
+                ..
+            },
+            Statement::Trait {
+                doc: b_doc,
+                visibility: b_visibility,
+                name: b_name,
+                methods: b_methods,
+                ..
+            },
+        ) => {
+            a_doc == b_doc
+                && a_visibility == b_visibility
+                && identifier_eq(a_name, b_name)
+                && slice_eq(a_methods, b_methods, method_signature_eq)
+        }
+        _ => false,
+    }
+}
+
+fn token_eq(a: &Token, b: &Token) -> bool {
+    a.raw == b.raw
+}
+
+fn identifier_eq(a: &IdentifierAST, b: &IdentifierAST) -> bool {
+    a.identifier == b.identifier
+}
+
+fn argument_eq(a: &Argument, b: &Argument) -> bool {
+    option_eq(a.name.as_ref(), b.name.as_ref(), identifier_eq)
+        && ast_eq_ignoring_locations(&a.value, &b.value)
+}
+
+fn block_eq(a: &StatementsBlock, b: &StatementsBlock) -> bool {
+    slice_eq(
+        &a.statements,
+        &b.statements,
+        statement_eq_ignoring_locations,
+    ) && option_eq(
+        a.tail.as_deref(),
+        b.tail.as_deref(),
+        ast_eq_ignoring_locations,
+    )
+}
+
+fn switch_case_eq(a: &SwitchCase, b: &SwitchCase) -> bool {
+    ast_eq_ignoring_locations(&a.value, &b.value) && block_eq(&a.block, &b.block)
+}
+
+fn enum_variant_eq(a: &EnumVariant, b: &EnumVariant) -> bool {
+    identifier_eq(&a.name, &b.name) && slice_eq(&a.fields, &b.fields, identifier_eq)
+}
+
+fn method_signature_eq(a: &MethodSignature, b: &MethodSignature) -> bool {
+    identifier_eq(&a.name, &b.name) && slice_eq(&a.parameters, &b.parameters, identifier_eq)
+}
+
+fn type_parameter_eq(a: &TypeParameter, b: &TypeParameter) -> bool {
+    identifier_eq(&a.name, &b.name) && option_eq(a.bound.as_ref(), b.bound.as_ref(), type_eq)
+}
+
+fn pattern_eq(a: &Pattern, b: &Pattern) -> bool {
+    match (a, b) {
+        (Pattern::Identifier(a), Pattern::Identifier(b)) => identifier_eq(a, b),
+        (Pattern::Tuple { elements: a, .. }, Pattern::Tuple { elements: b, .. })
+        | (Pattern::Array { elements: a, .. }, Pattern::Array { elements: b, .. }) => {
+            slice_eq(a, b, pattern_eq)
+        }
+        (Pattern::Rest { name: a, .. }, Pattern::Rest { name: b, .. }) => identifier_eq(a, b),
+        _ => false,
+    }
+}
+
+fn type_eq(a: &TypeAST, b: &TypeAST) -> bool {
+    match (a, b) {
+        (TypeAST::Name(a), TypeAST::Name(b)) => identifier_eq(a, b),
+        (TypeAST::Tuple { elements: a, .. }, TypeAST::Tuple { elements: b, .. }) => {
+            slice_eq(a, b, type_eq)
+        }
+        _ => false,
+    }
+}
+
+fn option_eq<T>(a: Option<&T>, b: Option<&T>, eq: impl Fn(&T, &T) -> bool) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn slice_eq<T>(a: &[T], b: &[T], eq: impl Fn(&T, &T) -> bool) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast::Expression, parser::Parser, token::Precedence};
+
+    use super::{ast_eq_ignoring_locations, statement_eq_ignoring_locations};
+
+    fn expression(source: &str) -> Expression {
+        Parser::new(source)
+            .parse_expression(Precedence::Lowest)
+            .unwrap()
+    }
+
+    #[test]
+    fn different_spans_compare_equal() {
+        let a = expression("a + f(b, c: 1)");
+        let b = expression("a   +   f( b ,  c :  1 )");
+
+        assert_ne!(a, b);
+        assert!(ast_eq_ignoring_locations(&a, &b));
+    }
+
+    #[test]
+    fn different_structures_compare_unequal() {
+        assert!(!ast_eq_ignoring_locations(
+            &expression("a + b * c"),
+            &expression("(a + b) * c")
+        ));
+        assert!(!ast_eq_ignoring_locations(
+            &expression("a + b"),
+            &expression("a - b")
+        ));
+    }
+
+    #[test]
+    fn statements() {
+        let a = Parser::new("var (a, b) = 1;").parse_statement().unwrap();
+        let b = Parser::new("var ( a,b )=1 ;").parse_statement().unwrap();
+        let c = Parser::new("var (a, c) = 1;").parse_statement().unwrap();
+
+        assert!(statement_eq_ignoring_locations(&a, &b));
+        assert!(!statement_eq_ignoring_locations(&a, &c));
+    }
+}
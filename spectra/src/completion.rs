@@ -0,0 +1,218 @@
+use crate::{
+    ast::{Expression, Module},
+    lexer::Lexer,
+    token::{Punctuation, RawToken},
+    visit::{self, Visitor},
+};
+use alloc::{borrow::ToOwned, format, string::String, vec};
+
+/// Stands in for whatever the user is about to type, so that incomplete
+/// input like `foo(a, ` still parses.
+const PLACEHOLDER: &str = "__completion__";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    /// The part of the identifier already typed before the cursor.
+    pub prefix: String,
+    pub context: CompletionContext,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompletionContext {
+    /// Anywhere an expression can start.
+    Expression,
+    /// `callee(a, |`: the cursor is in the argument at `index`.
+    CallArgument { callee: Expression, index: usize },
+    /// `receiver.|`
+    FieldAccess { receiver: Expression },
+}
+
+/// Works out what is being typed at `cursor` (a byte offset), ignoring
+/// everything after it. Unclosed brackets before the cursor are closed
+/// automatically, and a placeholder identifier is parsed in place of the
+/// cursor; the innermost construct around it decides the context.
+///
+/// Returns `None` if the input can't be completed into something that
+/// parses, e.g. when the cursor is inside a string literal.
+#[must_use]
+pub fn parse_for_completion(source: &str, cursor: usize) -> Option<Completion> {
+    let before_cursor = source.get(..cursor)?;
+    let mut completed = format!("{before_cursor}{PLACEHOLDER}");
+
+    let mut closers = vec![];
+
+    for token in Lexer::new(before_cursor) {
+        match token.raw {
+            RawToken::Punctuation(Punctuation::OpenParent) => closers.push(")"),
+            RawToken::Punctuation(Punctuation::OpenBracket) => closers.push("]"),
+            RawToken::Punctuation(Punctuation::OpenBrace) => closers.push(";}"),
+            RawToken::Punctuation(
+                Punctuation::CloseParent | Punctuation::CloseBracket | Punctuation::CloseBrace,
+            ) => {
+                closers.pop();
+            }
+            _ => {}
+        }
+    }
+
+    for closer in closers.iter().rev() {
+        completed.push_str(closer);
+    }
+
+    completed.push(';');
+
+    let module = completed.parse::<Module>().ok()?;
+
+    let mut finder = PlaceholderFinder { completion: None };
+
+    for statement in &module.statements {
+        finder.visit_statement(statement);
+    }
+
+    finder.completion
+}
+
+struct PlaceholderFinder {
+    completion: Option<Completion>,
+}
+
+fn typed_prefix(identifier: &str) -> Option<&str> {
+    identifier.strip_suffix(PLACEHOLDER)
+}
+
+impl Visitor for PlaceholderFinder {
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Identifier(identifier) => {
+                if let Some(prefix) = typed_prefix(&identifier.identifier) {
+                    match &mut self.completion {
+                        Some(completion) => completion.prefix = prefix.to_owned(),
+                        None => {
+                            self.completion = Some(Completion {
+                                prefix: prefix.to_owned(),
+                                context: CompletionContext::Expression,
+                            });
+                        }
+                    }
+                }
+            }
+            Expression::FieldAccess { left, right, .. } => {
+                if let Some(prefix) = typed_prefix(&right.identifier) {
+                    self.completion = Some(Completion {
+                        prefix: prefix.to_owned(),
+                        context: CompletionContext::FieldAccess {
+                            receiver: (**left).clone(),
+                        },
+                    });
+                }
+            }
+            Expression::Call {
+                callee, arguments, ..
+            } => {
+                if let Some(index) = arguments
+                    .iter()
+                    .position(|argument| contains_placeholder(&argument.value))
+                {
+                    self.completion = Some(Completion {
+                        prefix: String::new(),
+                        context: CompletionContext::CallArgument {
+                            callee: (**callee).clone(),
+                            index,
+                        },
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        visit::walk_expression(self, expression);
+    }
+}
+
+fn contains_placeholder(expression: &Expression) -> bool {
+    struct Search(bool);
+
+    impl Visitor for Search {
+        fn visit_expression(&mut self, expression: &Expression) {
+            match expression {
+                Expression::Identifier(identifier)
+                | Expression::FieldAccess {
+                    right: identifier, ..
+                } if typed_prefix(&identifier.identifier).is_some() => self.0 = true,
+                _ => {}
+            }
+
+            visit::walk_expression(self, expression);
+        }
+    }
+
+    let mut search = Search(false);
+    search.visit_expression(expression);
+    search.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{Expression, IdentifierAST},
+        token::Location,
+    };
+
+    use super::{parse_for_completion, Completion, CompletionContext};
+
+    fn identifier(name: &str, start: usize) -> Expression {
+        Expression::Identifier(IdentifierAST {
+            identifier: name.to_owned(),
+            location: Location {
+                start,
+                end: start + name.len(),
+            },
+        })
+    }
+
+    #[test]
+    fn unterminated_call() {
+        assert_eq!(
+            parse_for_completion("var x = 1; foo(a, ", 18),
+            Some(Completion {
+                prefix: String::new(),
+                context: CompletionContext::CallArgument {
+                    callee: identifier("foo", 11),
+                    index: 1,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn nested_call_inside_function() {
+        assert_eq!(
+            parse_for_completion("var f = fun () { foo(bar(ba", 27),
+            Some(Completion {
+                prefix: "ba".to_owned(),
+                context: CompletionContext::CallArgument {
+                    callee: identifier("bar", 21),
+                    index: 0,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn field_access() {
+        assert_eq!(
+            parse_for_completion("foo(a.b) + a.le; rest", 15),
+            Some(Completion {
+                prefix: "le".to_owned(),
+                context: CompletionContext::FieldAccess {
+                    receiver: identifier("a", 11),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn inside_string() {
+        assert_eq!(parse_for_completion("foo(\"ab", 7), None);
+    }
+}
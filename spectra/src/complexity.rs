@@ -0,0 +1,78 @@
+use crate::{
+    ast::{Expression, Statement},
+    visit::{self, Visitor},
+};
+
+/// Cyclomatic complexity of a function: one plus the number of decision
+/// points in its body. Nested function literals are scored on their own and
+/// don't contribute to the enclosing function's score.
+///
+/// Decision points are `do`/`while` loops and `switch` cases (`default` is the
+/// fall-back path and doesn't count).
+#[must_use]
+pub fn complexity(function: &Expression) -> usize {
+    let mut counter = DecisionCounter { decisions: 0 };
+
+    match function {
+        Expression::Function { block, .. } => counter.visit_statements_block(block),
+        expression => counter.visit_expression(expression),
+    }
+
+    counter.decisions + 1
+}
+
+struct DecisionCounter {
+    decisions: usize,
+}
+
+impl Visitor for DecisionCounter {
+    fn visit_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::DoWhile { .. } => self.decisions += 1,
+            Statement::Switch { cases, .. } => self.decisions += cases.len(),
+            _ => {}
+        }
+
+        visit::walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        if !matches!(expression, Expression::Function { .. }) {
+            visit::walk_expression(self, expression);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parser::Parser, token::Precedence};
+
+    use super::complexity;
+
+    fn function_complexity(source: &str) -> usize {
+        complexity(
+            &Parser::new(source)
+                .parse_expression(Precedence::Lowest)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn straight_line_function() {
+        assert_eq!(function_complexity("fun (a) { a; }"), 1);
+    }
+
+    #[test]
+    fn branching_function() {
+        assert_eq!(
+            function_complexity(
+                "fun (a) {
+                    do { a++; } while a;
+                    switch a { case 1 {} case 2 {} default {} }
+                    var inner = fun () { do {} while a; };
+                }"
+            ),
+            4
+        );
+    }
+}
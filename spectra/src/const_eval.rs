@@ -0,0 +1,236 @@
+use core::fmt::{self, Display};
+
+use crate::{
+    ast::{Argument, Expression, IdentifierAST, Literal, RawLiteral},
+    token::{Location, Punctuation, RawToken, Token},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstEvalError {
+    /// Anything but an integer literal, `+`, `-`, `*`, `/`, `**` or
+    /// parentheses, e.g. a variable or a call.
+    NotConstant(Location),
+    /// The result, or an intermediate one, doesn't fit in an `i64`.
+    Overflow(Location),
+    DivisionByZero(Location),
+    /// `2 ** -1`, which has no integer value.
+    NegativeExponent(Location),
+}
+
+impl Display for ConstEvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotConstant(..) => "expression is not a constant",
+            Self::Overflow(..) => "constant expression overflows",
+            Self::DivisionByZero(..) => "division by zero in a constant expression",
+            Self::NegativeExponent(..) => "negative exponent in a constant expression",
+        })
+    }
+}
+
+/// Evaluates an integer constant expression, such as the size in `[T; N]`.
+/// Only integer literals, unary `-`, `+`, `-`, `*`, `/`, `**`, parentheses
+/// and `len` of a string literal (in characters) or byte string literal (in
+/// bytes) are allowed. Division truncates toward zero.
+pub fn const_eval(expression: &Expression) -> Result<i64, ConstEvalError> {
+    let location = expression.location();
+
+    match expression {
+        Expression::Literal(Literal {
+            raw: RawLiteral::Integer(value),
+            ..
+        }) => i64::try_from(*value).map_err(|_| ConstEvalError::Overflow(location)),
+        Expression::Grouping { inner, .. } => const_eval(inner),
+        Expression::Call {
+            callee, arguments, ..
+        } if is_builtin(callee, "len") => {
+            let length = match arguments.as_slice() {
+                [Argument {
+                    name: None,
+                    value:
+                        Expression::Literal(Literal {
+                            raw: RawLiteral::String(value),
+                            ..
+                        }),
+                }] => value.chars().count(),
+                [Argument {
+                    name: None,
+                    value:
+                        Expression::Literal(Literal {
+                            raw: RawLiteral::Bytes(value),
+                            ..
+                        }),
+                }] => value.len(),
+                _ => return Err(ConstEvalError::NotConstant(location)),
+            };
+
+            i64::try_from(length).map_err(|_| ConstEvalError::Overflow(location))
+        }
+        // `-9223372036854775808` is `i64::MIN`, even though the literal on its
+        // own doesn't fit.
+        Expression::Prefix {
+            operator:
+                Token {
+                    raw: RawToken::Punctuation(Punctuation::Minus),
+                    ..
+                },
+            right,
+            ..
+        } if matches!(
+            **right,
+            Expression::Literal(Literal {
+                raw: RawLiteral::Integer(value),
+                ..
+            }) if value == i64::MIN.unsigned_abs()
+        ) =>
+        {
+            Ok(i64::MIN)
+        }
+        Expression::Prefix {
+            operator:
+                Token {
+                    raw: RawToken::Punctuation(Punctuation::Minus),
+                    ..
+                },
+            right,
+            ..
+        } => const_eval(right)?
+            .checked_neg()
+            .ok_or(ConstEvalError::Overflow(location)),
+        Expression::Binary {
+            left,
+            right,
+            operator:
+                Token {
+                    raw: RawToken::Punctuation(operator),
+                    ..
+                },
+            ..
+        } => {
+            let left = const_eval(left)?;
+            let right = const_eval(right)?;
+
+            let result = match operator {
+                Punctuation::Plus => left.checked_add(right),
+                Punctuation::Minus => left.checked_sub(right),
+                Punctuation::Star => left.checked_mul(right),
+                Punctuation::Slash if right == 0 => {
+                    return Err(ConstEvalError::DivisionByZero(location));
+                }
+                Punctuation::Slash => left.checked_div(right),
+                Punctuation::StarStar => {
+                    let exponent = u32::try_from(right).map_err(|_| {
+                        if right < 0 {
+                            ConstEvalError::NegativeExponent(location)
+                        } else {
+                            ConstEvalError::Overflow(location)
+                        }
+                    })?;
+
+                    left.checked_pow(exponent)
+                }
+                _ => return Err(ConstEvalError::NotConstant(location)),
+            };
+
+            result.ok_or(ConstEvalError::Overflow(location))
+        }
+        _ => Err(ConstEvalError::NotConstant(location)),
+    }
+}
+
+fn is_builtin(callee: &Expression, name: &str) -> bool {
+    matches!(callee, Expression::Identifier(IdentifierAST { identifier, .. }) if identifier == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parser::{Parser, ParserOptions},
+        token::{Location, Precedence},
+    };
+
+    use super::{const_eval, ConstEvalError};
+
+    fn eval(source: &str) -> Result<i64, ConstEvalError> {
+        let expression = Parser::new(source)
+            .parse_expression(Precedence::Lowest)
+            .unwrap();
+
+        const_eval(&expression)
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(eval("2 * 3 + 1"), Ok(7));
+        assert_eq!(eval("-7 / 2"), Ok(-3));
+        assert_eq!(eval("2 ** 10 - 1"), Ok(1023));
+    }
+
+    #[test]
+    fn minimum_integer() {
+        assert_eq!(eval("-9223372036854775808"), Ok(i64::MIN));
+        assert_eq!(
+            eval("9223372036854775808"),
+            Err(ConstEvalError::Overflow(Location { start: 0, end: 19 }))
+        );
+        assert_eq!(
+            eval("-9223372036854775808 - 1"),
+            Err(ConstEvalError::Overflow(Location { start: 0, end: 24 }))
+        );
+    }
+
+    #[test]
+    fn parentheses() {
+        let expression = Parser::new("2 * (3 + 1)")
+            .with_options(ParserOptions {
+                preserve_parens: true,
+                ..ParserOptions::default()
+            })
+            .parse_expression(Precedence::Lowest)
+            .unwrap();
+
+        assert_eq!(const_eval(&expression), Ok(8));
+    }
+
+    #[test]
+    fn string_length() {
+        assert_eq!(eval(r#"len("ab")"#), Ok(2));
+        assert_eq!(eval(r#"len("héllo") * 2"#), Ok(10));
+        assert_eq!(eval(r#"len(b"\xFF\x00")"#), Ok(2));
+    }
+
+    #[test]
+    fn length_of_non_constant() {
+        assert_eq!(
+            eval("len(x)"),
+            Err(ConstEvalError::NotConstant(Location { start: 0, end: 6 }))
+        );
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(
+            eval("1 / 0"),
+            Err(ConstEvalError::DivisionByZero(Location {
+                start: 0,
+                end: 5
+            }))
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(
+            eval("2 ** 63"),
+            Err(ConstEvalError::Overflow(Location { start: 0, end: 7 }))
+        );
+    }
+
+    #[test]
+    fn not_constant() {
+        assert_eq!(
+            eval("a + 1"),
+            Err(ConstEvalError::NotConstant(Location { start: 0, end: 1 }))
+        );
+    }
+}
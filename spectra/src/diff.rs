@@ -0,0 +1,125 @@
+use crate::{ast::Module, ast_eq::statement_eq_ignoring_locations};
+use alloc::{vec, vec::Vec};
+
+/// A top-level statement that differs between two versions of a module.
+/// Indices point into the old or new module's statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AstChange {
+    Added { index: usize },
+    Removed { index: usize },
+    Changed { old_index: usize, new_index: usize },
+}
+
+/// Statement-level diff of two modules. Statements are compared with
+/// [`statement_eq_ignoring_locations`], so edits that only move code around
+/// (e.g. reformatting) produce no changes.
+///
+/// Statements common to both are found with a longest common subsequence.
+/// Between two common statements, removed and added statements are paired
+/// up in order as [`AstChange::Changed`]; the rest are reported as removed
+/// or added.
+#[must_use]
+pub fn diff(old: &Module, new: &Module) -> Vec<AstChange> {
+    let old = &old.statements;
+    let new = &new.statements;
+
+    // common[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if statement_eq_ignoring_locations(&old[i], &new[j]) {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = vec![];
+    let mut removed = vec![];
+    let mut added = vec![];
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && statement_eq_ignoring_locations(&old[i], &new[j]) {
+            flush(&mut changes, &mut removed, &mut added);
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || common[i][j + 1] >= common[i + 1][j]) {
+            added.push(j);
+            j += 1;
+        } else {
+            removed.push(i);
+            i += 1;
+        }
+    }
+
+    flush(&mut changes, &mut removed, &mut added);
+    changes
+}
+
+fn flush(changes: &mut Vec<AstChange>, removed: &mut Vec<usize>, added: &mut Vec<usize>) {
+    let paired = removed.len().min(added.len());
+
+    changes.extend(
+        removed
+            .iter()
+            .zip(added.iter())
+            .map(|(&old_index, &new_index)| AstChange::Changed {
+                old_index,
+                new_index,
+            }),
+    );
+    changes.extend(
+        removed[paired..]
+            .iter()
+            .map(|&index| AstChange::Removed { index }),
+    );
+    changes.extend(
+        added[paired..]
+            .iter()
+            .map(|&index| AstChange::Added { index }),
+    );
+
+    removed.clear();
+    added.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::Module;
+
+    use super::{diff, AstChange};
+
+    fn module(source: &str) -> Module {
+        source.parse().unwrap()
+    }
+
+    #[test]
+    fn added_statement() {
+        assert_eq!(
+            diff(&module("a; b;"), &module("a;\n\nc;\nb;")),
+            vec![AstChange::Added { index: 1 }]
+        );
+    }
+
+    #[test]
+    fn removed_and_changed_statements() {
+        assert_eq!(
+            diff(&module("a; b; c; d;"), &module("a; x; d;")),
+            vec![
+                AstChange::Changed {
+                    old_index: 1,
+                    new_index: 1
+                },
+                AstChange::Removed { index: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_modules() {
+        assert_eq!(diff(&module("a; b;"), &module("a;b;")), vec![]);
+    }
+}
@@ -0,0 +1,221 @@
+use alloc::collections::VecDeque;
+use core::iter::Peekable;
+
+use crate::{
+    ast::{Expression, ExpressionKind, Statement, StatementKind, StatementsBlock},
+    lexer::Lexer,
+    parser::{ParseError, Parser},
+    token::{Location, Token},
+    visit::{self, Visitor},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseEvent {
+    EnterNode(NodeKind, Location),
+    LeaveNode,
+    Token(Token),
+    /// The source failed to parse here. No events follow an error.
+    Error(ParseError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    StatementsBlock,
+    Statement(StatementKind),
+    Expression(ExpressionKind),
+}
+
+/// Parses `source` as a stream of events instead of a [`Module`]. Only one
+/// top-level statement's AST is built at a time, but `source` is lexed
+/// twice: once for the parser and once for the tokens the AST doesn't keep.
+///
+/// Every token the [`Lexer`] produces appears as exactly one
+/// [`ParseEvent::Token`], nested inside the innermost node whose location
+/// covers it. Doc comments are tokens, but plain `//` and `/* */` comments
+/// are skipped by the lexer and never show up.
+///
+/// [`Module`]: crate::ast::Module
+pub fn parse_events(source: &str) -> ParseEvents<'_> {
+    let mut parser = Parser::new(source);
+    parser.parse_module_doc();
+
+    ParseEvents {
+        parser,
+        tokens: Lexer::new(source).peekable(),
+        pending: VecDeque::new(),
+        done: false,
+    }
+}
+
+pub struct ParseEvents<'s> {
+    parser: Parser<'s>,
+    /// A second pass over the source, used to fill in the tokens the AST
+    /// doesn't keep.
+    tokens: Peekable<Lexer<'s>>,
+    pending: VecDeque<ParseEvent>,
+    done: bool,
+}
+
+impl Iterator for ParseEvents<'_> {
+    type Item = ParseEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+
+            if self.done {
+                return None;
+            }
+
+            if self.parser.is_at_end() {
+                self.pending
+                    .extend(self.tokens.by_ref().map(ParseEvent::Token));
+                self.done = true;
+                continue;
+            }
+
+            match self.parser.parse_statement() {
+                Ok(statement) => EventEmitter {
+                    tokens: &mut self.tokens,
+                    events: &mut self.pending,
+                }
+                .visit_statement(&statement),
+                Err(error) => {
+                    self.pending.push_back(ParseEvent::Error(error));
+                    self.done = true;
+                }
+            }
+        }
+    }
+}
+
+struct EventEmitter<'a, 's> {
+    tokens: &'a mut Peekable<Lexer<'s>>,
+    events: &'a mut VecDeque<ParseEvent>,
+}
+
+impl EventEmitter<'_, '_> {
+    fn tokens_while(&mut self, predicate: impl Fn(&Token) -> bool) {
+        while let Some(token) = self.tokens.next_if(&predicate) {
+            self.events.push_back(ParseEvent::Token(token));
+        }
+    }
+
+    fn enter(&mut self, kind: NodeKind, location: Location) {
+        self.tokens_while(|token| token.location.start < location.start);
+        self.events.push_back(ParseEvent::EnterNode(kind, location));
+    }
+
+    fn leave(&mut self, location: Location) {
+        self.tokens_while(|token| token.location.end <= location.end);
+        self.events.push_back(ParseEvent::LeaveNode);
+    }
+}
+
+impl Visitor for EventEmitter<'_, '_> {
+    fn visit_statements_block(&mut self, block: &StatementsBlock) {
+        self.enter(NodeKind::StatementsBlock, block.location);
+        visit::walk_statements_block(self, block);
+        self.leave(block.location);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        let location = match statement {
+            Statement::Expression { location, .. }
+            | Statement::Return { location, .. }
+            | Statement::Break { location, .. }
+            | Statement::Continue { location }
+            | Statement::Var { location, .. }
+            | Statement::Empty { location }
+            | Statement::DoWhile { location, .. }
+            | Statement::Switch { location, .. }
+            | Statement::Enum { location, .. }
+            | Statement::Trait { location, .. }
+            | Statement::Yield { location, .. }
+            | Statement::Assert { location, .. }
+            | Statement::Defer { location, .. } => location,
+        };
+
+        self.enter(NodeKind::Statement(statement.kind()), *location);
+        visit::walk_statement(self, statement);
+        self.leave(*location);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        self.enter(
+            NodeKind::Expression(expression.kind()),
+            expression.location(),
+        );
+        visit::walk_expression(self, expression);
+        self.leave(expression.location());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{ExpressionKind, StatementKind},
+        token::{Location, Punctuation, RawToken, Token},
+    };
+
+    use super::{parse_events, NodeKind, ParseEvent};
+
+    fn token(raw: impl Into<RawToken>, start: usize, end: usize) -> ParseEvent {
+        ParseEvent::Token(Token {
+            raw: raw.into(),
+            location: Location { start, end },
+        })
+    }
+
+    fn integer(value: u64, start: usize) -> ParseEvent {
+        token(
+            RawToken::IntegerLiteral {
+                value,
+                source_text: value.to_string(),
+            },
+            start,
+            start + 1,
+        )
+    }
+
+    fn enter(kind: NodeKind, start: usize, end: usize) -> ParseEvent {
+        ParseEvent::EnterNode(kind, Location { start, end })
+    }
+
+    #[test]
+    fn binary_expression() {
+        assert_eq!(
+            parse_events("1 + 2;").collect::<Vec<_>>(),
+            vec![
+                enter(NodeKind::Statement(StatementKind::Expression), 0, 6),
+                enter(NodeKind::Expression(ExpressionKind::Binary), 0, 5),
+                enter(NodeKind::Expression(ExpressionKind::Literal), 0, 1),
+                integer(1, 0),
+                ParseEvent::LeaveNode,
+                token(Punctuation::Plus, 2, 3),
+                enter(NodeKind::Expression(ExpressionKind::Literal), 4, 5),
+                integer(2, 4),
+                ParseEvent::LeaveNode,
+                ParseEvent::LeaveNode,
+                token(Punctuation::Semicolon, 5, 6),
+                ParseEvent::LeaveNode,
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_parse_error() {
+        let events = parse_events("; 1 +").collect::<Vec<_>>();
+
+        assert!(matches!(events.last(), Some(ParseEvent::Error(..))));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, ParseEvent::Error(..)))
+                .count(),
+            1
+        );
+    }
+}
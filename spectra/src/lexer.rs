@@ -1,7 +1,11 @@
-use std::str::Chars;
+use alloc::{borrow::ToOwned, collections::BTreeMap, string::String, vec, vec::Vec};
+use core::str::Chars;
 
-use crate::token::{Location, Punctuation, RawToken, Token, KEYWORDS};
+use crate::token::{
+    EscapeError, EscapeErrorKind, LexError, Location, Punctuation, RawToken, Token, KEYWORDS,
+};
 
+#[derive(Clone)]
 pub struct Lexer<'s> {
     source: &'s str,
     chars: Chars<'s>,
@@ -10,6 +14,21 @@ pub struct Lexer<'s> {
 
     current: char,
     next: char,
+
+    escapes: BTreeMap<char, String>,
+
+    /// Whether only whitespace, comments and `//!` lines have been seen so
+    /// far. `//!` lines are plain comments everywhere else.
+    in_module_header: bool,
+
+    /// Whether `source` starts the file, so that a `#!` line at offset 0 is
+    /// a shebang rather than a directive.
+    at_file_start: bool,
+
+    emoji_identifiers: bool,
+
+    /// See [`Lexer::char_offsets`]. `None` unless recording was enabled.
+    char_offsets: Option<Vec<usize>>,
 }
 
 impl<'s> Lexer<'s> {
@@ -25,9 +44,82 @@ impl<'s> Lexer<'s> {
             offset: 0,
             current,
             next,
+            escapes: BTreeMap::new(),
+            in_module_header: true,
+            at_file_start: true,
+            emoji_identifiers: false,
+            char_offsets: None,
         }
     }
 
+    /// Treats `//!` lines as plain comments from the start, for lexing
+    /// source that continues a file whose header was already lexed.
+    pub fn after_module_header(mut self) -> Self {
+        self.in_module_header = false;
+        self
+    }
+
+    /// Lexes a leading `#!` line as a directive rather than a shebang, for
+    /// lexing source that continues a file past its first line.
+    pub fn after_first_line(mut self) -> Self {
+        self.at_file_start = false;
+        self
+    }
+
+    /// Registers `\<escape>` to decode into `replacement` inside string
+    /// literals. The standard escapes can't be overridden.
+    pub fn with_escape(mut self, escape: char, replacement: impl Into<String>) -> Self {
+        self.escapes.insert(escape, replacement.into());
+        self
+    }
+
+    /// Also accepts emoji in identifiers, keeping ZWJ sequences, skin tone
+    /// modifiers and variation selectors together with the emoji they
+    /// modify.
+    pub fn with_emoji_identifiers(mut self) -> Self {
+        self.emoji_identifiers = true;
+        self
+    }
+
+    /// Records where each decoded character of a `"..."` literal is written
+    /// in the source, available through [`Lexer::char_offsets`].
+    pub fn with_char_offsets(mut self) -> Self {
+        self.char_offsets = Some(vec![]);
+        self
+    }
+
+    /// For the most recently lexed `"..."` literal, the source byte offset of
+    /// each character of the decoded string: `offsets[i]` is where the `i`th
+    /// character starts. A character produced by an escape maps to its `\`.
+    /// `None` unless enabled with [`Lexer::with_char_offsets`].
+    pub fn char_offsets(&self) -> Option<&[usize]> {
+        self.char_offsets.as_deref()
+    }
+
+    /// Every [`RawToken::UnexpectedChar`] and [`RawToken::Error`] token in
+    /// `source`, for reporting lexical problems before parsing.
+    pub fn collect_errors(source: &'s str) -> Vec<Token> {
+        Self::new(source)
+            .filter(|token| {
+                matches!(
+                    token.raw,
+                    RawToken::UnexpectedChar(..) | RawToken::Error(..)
+                )
+            })
+            .collect()
+    }
+
+    /// The part of the source that hasn't been turned into tokens yet.
+    pub fn remaining(&self) -> &'s str {
+        self.source.get(self.offset..).unwrap_or_default()
+    }
+
+    /// Skips ahead to the next `c` without consuming it, e.g. to resume
+    /// lexing at the next `;` after an error. Returns the skipped source.
+    pub fn skip_to_char(&mut self, c: char) -> &'s str {
+        self.advance_while(self.offset, |current, _| current != c)
+    }
+
     fn advance(&mut self) {
         let previous = self.current;
 
@@ -64,10 +156,95 @@ impl<'s> Lexer<'s> {
         }
     }
 
+    /// Returns an error token if a block comment runs to the end of input.
+    fn skip_whitespaces_and_comments(&mut self) -> Option<Token> {
+        loop {
+            self.skip_whitespaces();
+
+            // A `#!/usr/bin/env spectra` line at the very start of the file.
+            let at_shebang =
+                self.at_file_start && self.offset == 0 && self.current == '#' && self.next == '!';
+
+            if at_shebang
+                || (self.current == '/'
+                    && self.next == '/'
+                    && !self.at_doc_comment()
+                    && !(self.in_module_header && self.at_module_doc_comment()))
+            {
+                self.advance_while(self.offset, |current, _| current != '\n');
+            } else if self.current == '/' && self.next == '*' {
+                if let Some(error) = self.skip_block_comment() {
+                    return Some(error);
+                }
+            } else {
+                return None;
+            }
+        }
+    }
+
+    /// Skips a `/* ... */` comment, where each `/*` inside needs its own
+    /// `*/`.
+    fn skip_block_comment(&mut self) -> Option<Token> {
+        let start_offset = self.offset;
+        let mut depth = 0_usize;
+
+        loop {
+            match (self.current, self.next) {
+                ('/', '*') => {
+                    depth += 1;
+                    self.advance_twice();
+                }
+                ('*', '/') => {
+                    depth -= 1;
+                    self.advance_twice();
+
+                    if depth == 0 {
+                        return None;
+                    }
+                }
+                _ if self.eof() => {
+                    return Some(Token {
+                        raw: RawToken::Error(LexError::UnterminatedBlockComment),
+                        location: self.location_from(start_offset),
+                    });
+                }
+                _ => self.advance(),
+            }
+        }
+    }
+
+    fn peek_third(&self) -> char {
+        self.chars.clone().next().unwrap_or('\0')
+    }
+
+    /// `///`, but not `////` and longer, which are plain comments.
+    fn at_doc_comment(&self) -> bool {
+        self.current == '/'
+            && self.next == '/'
+            && self.peek_third() == '/'
+            && self.chars.clone().nth(1) != Some('/')
+    }
+
+    fn at_module_doc_comment(&self) -> bool {
+        self.current == '/' && self.next == '/' && self.peek_third() == '!'
+    }
+
+    /// Whether only indentation precedes the current character on its line.
+    fn at_line_start(&self) -> bool {
+        self.source[..self.offset]
+            .rsplit('\n')
+            .next()
+            .is_some_and(|line| line.chars().all(is_whitespace))
+    }
+
+    fn at_triple_quote(&self) -> bool {
+        self.current == '"' && self.next == '"' && self.peek_third() == '"'
+    }
+
     fn current_char_location(&self) -> Location {
         Location {
             start: self.offset,
-            end: self.offset + 1,
+            end: self.offset + self.current.len_utf8(),
         }
     }
 
@@ -101,10 +278,26 @@ impl<'s> Lexer<'s> {
         token
     }
 
+    fn advance_thrice_with(&mut self, raw: impl Into<RawToken>) -> Token {
+        let token = Token {
+            raw: raw.into(),
+            location: Location {
+                start: self.offset,
+                end: self.offset + 3,
+            },
+        };
+
+        self.advance_twice();
+        self.advance();
+        token
+    }
+
     fn next_identifier_or_keyword_token(&mut self) -> Token {
         let start_offset = self.offset;
-        let identifier_candidate =
-            self.advance_while(start_offset, |current, _| is_id_continue(current));
+        let emoji_identifiers = self.emoji_identifiers;
+        let identifier_candidate = self.advance_while(start_offset, |current, _| {
+            is_id_continue(current) || (emoji_identifiers && is_emoji_continue(current))
+        });
 
         if let Some(keyword) = KEYWORDS.get(identifier_candidate) {
             Token {
@@ -119,164 +312,1622 @@ impl<'s> Lexer<'s> {
         }
     }
 
-    // TODO: process floating-point numbers
+    /// A number immediately followed by identifier characters (`10px`) is a
+    /// single error token rather than a number and an identifier.
     fn next_number_token(&mut self) -> Token {
         let start_offset = self.offset;
-        let number_string = self.advance_while(start_offset, |current, _| current.is_ascii_digit());
+        let token = self.next_unchecked_number_token();
+
+        if !is_id_start(self.current) {
+            return token;
+        }
+
+        self.advance_while(start_offset, |current, _| is_id_continue(current));
 
         Token {
-            raw: RawToken::IntegerLiteral(number_string.parse().unwrap()),
+            raw: RawToken::Error(LexError::TrailingCharactersAfterNumber),
             location: self.location_from(start_offset),
         }
     }
 
-    fn next_string_token(&mut self) -> Token {
+    fn next_unchecked_number_token(&mut self) -> Token {
+        let radix = match (self.current, self.next) {
+            ('0', 'x') => 16,
+            ('0', 'o') => 8,
+            ('0', 'b') => 2,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            return self.next_radix_integer_token(radix);
+        }
+
         let start_offset = self.offset;
+        let number_string =
+            self.advance_while(start_offset, |current, _| is_decimal_digit(current));
 
-        let string = self.advance_while(start_offset, |current, _| current != '"');
+        let has_fraction = self.current == '.' && self.next.is_ascii_digit();
 
-        Token {
-            raw: RawToken::StringLiteral(string.to_owned()),
-            location: self.location_from(start_offset),
+        if !has_fraction && !self.at_exponent() {
+            return Token {
+                raw: integer_literal(number_string, 10, number_string),
+                location: self.location_from(start_offset),
+            };
         }
-    }
-}
 
-impl Iterator for Lexer<'_> {
-    type Item = Token;
+        if has_fraction {
+            self.advance();
+            self.advance_while(start_offset, |current, _| is_decimal_digit(current));
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.skip_whitespaces();
+            if self.current == '.' && self.next.is_ascii_digit() {
+                self.advance_while(start_offset, |current, next| {
+                    is_decimal_digit(current) || (current == '.' && next.is_ascii_digit())
+                });
 
-        if self.eof() {
-            return None;
+                return Token {
+                    raw: RawToken::Error(LexError::MultipleDecimalPoints),
+                    location: self.location_from(start_offset),
+                };
+            }
         }
 
-        Some(match (self.current, self.next) {
-            ('+', '+') => self.advance_twice_with(Punctuation::PlusPlus),
-            ('+', '=') => self.advance_twice_with(Punctuation::PlusEq),
-            ('+', _) => self.advance_with(Punctuation::Plus),
-            ('-', '-') => self.advance_twice_with(Punctuation::MinusMinus),
-            ('-', '=') => self.advance_twice_with(Punctuation::MinusEq),
-            ('-', _) => self.advance_with(Punctuation::Minus),
-            ('/', '=') => self.advance_twice_with(Punctuation::SlashEq),
-            ('*', '=') => self.advance_twice_with(Punctuation::StarEq),
-            ('*', '*') => self.advance_with(Punctuation::StarStar),
-            ('*', _) => self.advance_with(Punctuation::Star),
-            ('/', _) => self.advance_with(Punctuation::Slash),
-            ('(', _) => self.advance_with(Punctuation::OpenParent),
-            (')', _) => self.advance_with(Punctuation::CloseParent),
-            ('[', _) => self.advance_with(Punctuation::OpenBracket),
-            (']', _) => self.advance_with(Punctuation::CloseBracket),
-            ('{', _) => self.advance_with(Punctuation::OpenBrace),
-            ('}', _) => self.advance_with(Punctuation::CloseBrace),
-            (';', _) => self.advance_with(Punctuation::Semicolon),
-            (',', _) => self.advance_with(Punctuation::Comma),
-            ('.', _) => self.advance_with(Punctuation::Dot),
-            ('=', _) => self.advance_with(Punctuation::Eq),
-            ('"', _) => self.next_string_token(),
-            (_, _) => {
-                if is_id_start(self.current) {
-                    self.next_identifier_or_keyword_token()
-                } else if self.current.is_ascii_digit() {
-                    self.next_number_token()
-                } else {
-                    Token {
-                        raw: RawToken::UnexpectedChar(self.current),
-                        location: self.current_char_location(),
-                    }
-                }
+        if self.at_exponent() {
+            self.advance();
+
+            if matches!(self.current, '+' | '-') {
+                self.advance();
             }
-        })
+        }
+
+        let number_string =
+            self.advance_while(start_offset, |current, _| is_decimal_digit(current));
+
+        Token {
+            raw: RawToken::FloatLiteral {
+                // Always a valid float; too large a value parses as infinity.
+                value: number_string.replace('_', "").parse().unwrap(),
+                source_text: number_string.to_owned(),
+            },
+            location: self.location_from(start_offset),
+        }
     }
-}
 
-pub fn is_whitespace(c: char) -> bool {
-    matches!(
-        c,
-        // Usual ASCII suspects
-        '\u{0009}'   // \t
-        | '\u{000A}' // \n
-        | '\u{000B}' // vertical tab
-        | '\u{000C}' // form feed
-        | '\u{000D}' // \r
-        | '\u{0020}' // space
+    /// Whether an exponent like `e10` or `E-3` starts here.
+    fn at_exponent(&self) -> bool {
+        matches!(self.current, 'e' | 'E')
+            && (self.next.is_ascii_digit()
+                || (matches!(self.next, '+' | '-') && self.peek_third().is_ascii_digit()))
+    }
 
-        // NEXT LINE from latin1
-        | '\u{0085}'
+    /// Lexes `0x`, `0o` or `0b` and the digits after it. Letters and
+    /// out-of-range digits are taken as part of the literal, so `0b102` is
+    /// one error token rather than `0b10` followed by `2`.
+    fn next_radix_integer_token(&mut self, radix: u32) -> Token {
+        let start_offset = self.offset;
 
-        // Bidi markers
-        | '\u{200E}' // LEFT-TO-RIGHT MARK
-        | '\u{200F}' // RIGHT-TO-LEFT MARK
+        self.advance_twice();
 
-        // Dedicated whitespace characters from Unicode
-        | '\u{2028}' // LINE SEPARATOR
-        | '\u{2029}' // PARAGRAPH SEPARATOR
-    )
-}
+        let digits_offset = self.offset;
+        let digits = self.advance_while(digits_offset, |current, _| is_id_continue(current));
 
-fn is_id_start(c: char) -> bool {
-    c == '_' || unicode_xid::UnicodeXID::is_xid_start(c)
-}
+        let raw = if let Some((index, digit)) = digits
+            .char_indices()
+            .find(|(_, digit)| !(digit.is_digit(radix) || *digit == '_'))
+        {
+            RawToken::Error(LexError::InvalidDigit {
+                digit,
+                radix,
+                location: Location {
+                    start: digits_offset + index,
+                    end: digits_offset + index + digit.len_utf8(),
+                },
+            })
+        } else if digits.chars().all(|digit| digit == '_') {
+            RawToken::Error(LexError::MissingDigits)
+        } else {
+            integer_literal(digits, radix, &self.source[start_offset..self.offset])
+        };
 
-fn is_id_continue(c: char) -> bool {
-    unicode_xid::UnicodeXID::is_xid_continue(c)
-}
+        Token {
+            raw,
+            location: self.location_from(start_offset),
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use crate::token::{Location, Punctuation, RawToken, Token};
+    fn next_doc_comment_token(&mut self) -> Token {
+        let start_offset = self.offset;
 
-    use super::Lexer;
+        self.advance_twice();
+        self.advance();
 
-    #[test]
-    fn eof() {
-        let mut lexer = Lexer::new("");
-        assert_eq!(lexer.next(), None);
+        let text = self.advance_while(self.offset, |current, _| current != '\n');
+
+        Token {
+            raw: RawToken::DocComment(text.strip_prefix(' ').unwrap_or(text).to_owned()),
+            location: self.location_from(start_offset),
+        }
     }
 
-    #[test]
-    fn identifier() {
-        let mut lexer = Lexer::new("test");
-        assert_eq!(
-            lexer.next(),
-            Some(Token {
-                raw: RawToken::Identifier("test".to_owned()),
-                location: Location { start: 0, end: 4 }
-            })
-        );
+    fn next_module_doc_comment_token(&mut self) -> Token {
+        let start_offset = self.offset;
+
+        self.advance_twice();
+        self.advance();
+
+        let text = self.advance_while(self.offset, |current, _| current != '\n');
+
+        Token {
+            raw: RawToken::ModuleDocComment(text.strip_prefix(' ').unwrap_or(text).to_owned()),
+            location: self.location_from(start_offset),
+        }
     }
 
-    #[test]
-    fn bool() {
-        let mut lexer = Lexer::new("true false");
+    fn next_directive_token(&mut self) -> Token {
+        let start_offset = self.offset;
 
-        assert_eq!(
-            lexer.next(),
-            Some(Token {
-                raw: RawToken::BoolLiteral(true),
-                location: Location { start: 0, end: 4 }
-            })
-        );
-        assert_eq!(
-            lexer.next(),
-            Some(Token {
-                raw: RawToken::BoolLiteral(false),
-                location: Location { start: 5, end: 10 }
-            })
-        );
+        self.advance();
+
+        let text = self.advance_while(self.offset, |current, _| current != '\n');
+
+        Token {
+            raw: RawToken::Directive(text.trim_end().to_owned()),
+            location: self.location_from(start_offset),
+        }
     }
 
-    #[test]
-    fn punctuation() {
-        let mut lexer = Lexer::new("+");
+    fn next_block_string_token(&mut self) -> Token {
+        let start_offset = self.offset;
 
-        assert_eq!(
-            lexer.next(),
-            Some(Token {
-                raw: RawToken::Punctuation(Punctuation::Plus),
-                location: Location { start: 0, end: 1 }
-            })
+        self.advance_twice();
+        self.advance();
+
+        let content_start_offset = self.offset;
+
+        while !self.at_triple_quote() {
+            if self.eof() {
+                return Token {
+                    raw: RawToken::Error(LexError::UnterminatedString),
+                    location: self.location_from(start_offset),
+                };
+            }
+
+            self.advance();
+        }
+
+        let content = &self.source[content_start_offset..self.offset];
+
+        self.advance_twice();
+        self.advance();
+
+        Token {
+            raw: RawToken::StringLiteral(strip_block_indentation(content)),
+            location: self.location_from(start_offset),
+        }
+    }
+
+    fn next_string_token(&mut self) -> Token {
+        let start_offset = self.offset;
+
+        self.advance();
+
+        let mut string = String::new();
+        let mut escape_error = None;
+
+        if let Some(offsets) = &mut self.char_offsets {
+            offsets.clear();
+        }
+
+        while self.current != '"' {
+            if self.eof() {
+                return Token {
+                    raw: RawToken::Error(LexError::UnterminatedString),
+                    location: self.location_from(start_offset),
+                };
+            }
+
+            let char_offset = self.offset;
+            let decoded_start = string.len();
+
+            if self.current == '\\' {
+                self.advance();
+
+                if let Err(error) = self.push_escape(&mut string) {
+                    escape_error.get_or_insert(error);
+                }
+            } else {
+                string.push(self.current);
+                self.advance();
+            }
+
+            if let Some(offsets) = &mut self.char_offsets {
+                offsets.extend(string[decoded_start..].chars().map(|_| char_offset));
+            }
+        }
+
+        self.advance();
+
+        Token {
+            raw: match escape_error {
+                Some(error) => RawToken::Error(LexError::InvalidEscape(error)),
+                None => RawToken::StringLiteral(string),
+            },
+            location: self.location_from(start_offset),
+        }
+    }
+
+    fn next_byte_string_token(&mut self) -> Token {
+        let start_offset = self.offset;
+
+        self.advance_twice();
+
+        let mut bytes = vec![];
+        let mut escape_error = None;
+
+        while self.current != '"' {
+            if self.eof() {
+                return Token {
+                    raw: RawToken::Error(LexError::UnterminatedString),
+                    location: self.location_from(start_offset),
+                };
+            }
+
+            if self.current == '\\' {
+                self.advance();
+
+                let result = if self.current == 'x' {
+                    self.decode_byte_escape().map(|byte| bytes.push(byte))
+                } else {
+                    let mut decoded = String::new();
+                    let result = self.push_escape(&mut decoded);
+                    bytes.extend_from_slice(decoded.as_bytes());
+                    result
+                };
+
+                if let Err(error) = result {
+                    escape_error.get_or_insert(error);
+                }
+            } else {
+                let mut buffer = [0; 4];
+                bytes.extend_from_slice(self.current.encode_utf8(&mut buffer).as_bytes());
+                self.advance();
+            }
+        }
+
+        self.advance();
+
+        Token {
+            raw: match escape_error {
+                Some(error) => RawToken::Error(LexError::InvalidEscape(error)),
+                None => RawToken::ByteStringLiteral(bytes),
+            },
+            location: self.location_from(start_offset),
+        }
+    }
+
+    /// `'a'` or `'\n'`: exactly one character or escape between quotes.
+    fn next_char_token(&mut self) -> Token {
+        let start_offset = self.offset;
+
+        self.advance();
+
+        let error = |lexer: &Self, error| Token {
+            raw: RawToken::Error(error),
+            location: lexer.location_from(start_offset),
+        };
+
+        if self.current == '\'' {
+            self.advance();
+            return error(self, LexError::EmptyChar);
+        }
+
+        if self.eof() {
+            return error(self, LexError::UnterminatedChar);
+        }
+
+        let value = if self.current == '\\' {
+            self.advance();
+
+            if self.eof() {
+                return error(self, LexError::UnterminatedChar);
+            }
+
+            self.decode_escape()
+        } else {
+            let value = self.current;
+            self.advance();
+            Ok(value)
+        };
+
+        if self.current != '\'' {
+            let line = self.remaining().split('\n').next().unwrap_or_default();
+
+            if !line.contains('\'') {
+                return error(self, LexError::UnterminatedChar);
+            }
+
+            // Consume the rest of `'ab'`, so that `b'` isn't lexed as the
+            // start of another literal.
+            self.skip_to_char('\'');
+            self.advance();
+            return error(self, LexError::MultipleCharsInChar);
+        }
+
+        self.advance();
+
+        Token {
+            raw: match value {
+                Ok(value) => RawToken::CharLiteral(value),
+                Err(escape_error) => RawToken::Error(LexError::InvalidEscape(escape_error)),
+            },
+            location: self.location_from(start_offset),
+        }
+    }
+
+    /// Decodes an escape in a string literal into `string`. Custom escapes
+    /// are only looked up when no standard one matches.
+    fn push_escape(&mut self, string: &mut String) -> Result<(), EscapeError> {
+        if self.eof() {
+            return Ok(());
+        }
+
+        match self.decode_escape() {
+            Ok(c) => string.push(c),
+            Err(
+                error @ EscapeError {
+                    kind: EscapeErrorKind::Unknown(escape),
+                    ..
+                },
+            ) => match self.escapes.get(&escape) {
+                Some(replacement) => string.push_str(replacement),
+                None => return Err(error),
+            },
+            Err(error) => return Err(error),
+        }
+
+        Ok(())
+    }
+
+    /// Decodes `\xNN` in a byte string, where `x` must be the current
+    /// character. Unlike in [`Lexer::decode_escape`], any byte is allowed.
+    fn decode_byte_escape(&mut self) -> Result<u8, EscapeError> {
+        let start_offset = self.offset - 1;
+
+        self.advance();
+
+        if !(self.current.is_ascii_hexdigit() && self.next.is_ascii_hexdigit()) {
+            return Err(EscapeError {
+                kind: EscapeErrorKind::MalformedHex,
+                location: self.location_from(start_offset),
+            });
+        }
+
+        let digits = &self.source[self.offset..self.offset + 2];
+        self.advance_twice();
+
+        Ok(u8::from_str_radix(digits, 16).expect("two hex digits"))
+    }
+
+    /// Decodes the escape sequence following a `\`, which must be the
+    /// previous character, and advances past it. Shared by every literal
+    /// that supports escapes.
+    fn decode_escape(&mut self) -> Result<char, EscapeError> {
+        let start_offset = self.offset - 1;
+        let escape = self.current;
+
+        self.advance();
+
+        let error = |lexer: &Self, kind| EscapeError {
+            kind,
+            location: lexer.location_from(start_offset),
+        };
+
+        match escape {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            '0' => Ok('\0'),
+            '\\' | '"' | '\'' => Ok(escape),
+            'x' => {
+                if !(self.current.is_ascii_hexdigit() && self.next.is_ascii_hexdigit()) {
+                    return Err(error(self, EscapeErrorKind::MalformedHex));
+                }
+
+                let digits = &self.source[self.offset..self.offset + 2];
+                self.advance_twice();
+
+                let value = u32::from_str_radix(digits, 16).expect("two hex digits");
+
+                if value > 0x7F {
+                    return Err(error(self, EscapeErrorKind::NonAsciiHex(value)));
+                }
+
+                Ok(char::from(value as u8))
+            }
+            'u' => {
+                if self.current != '{' {
+                    return Err(error(self, EscapeErrorKind::MalformedUnicode));
+                }
+
+                self.advance();
+
+                let digits =
+                    self.advance_while(self.offset, |current, _| current.is_ascii_hexdigit());
+
+                if digits.is_empty() || digits.len() > 6 || self.current != '}' {
+                    return Err(error(self, EscapeErrorKind::MalformedUnicode));
+                }
+
+                self.advance();
+
+                let value = u32::from_str_radix(digits, 16).expect("1 to 6 hex digits");
+
+                char::from_u32(value).ok_or_else(|| {
+                    error(
+                        self,
+                        if (0xD800..=0xDFFF).contains(&value) {
+                            EscapeErrorKind::Surrogate(value)
+                        } else {
+                            EscapeErrorKind::OutOfRange(value)
+                        },
+                    )
+                })
+            }
+            _ => Err(error(self, EscapeErrorKind::Unknown(escape))),
+        }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.skip_whitespaces_and_comments() {
+            return Some(error);
+        }
+
+        if self.eof() {
+            return None;
+        }
+
+        if self.in_module_header {
+            if self.at_module_doc_comment() {
+                return Some(self.next_module_doc_comment_token());
+            }
+
+            self.in_module_header = false;
+        }
+
+        Some(match (self.current, self.next) {
+            ('+', '+') => self.advance_twice_with(Punctuation::PlusPlus),
+            ('+', '=') => self.advance_twice_with(Punctuation::PlusEq),
+            ('+', _) => self.advance_with(Punctuation::Plus),
+            ('-', '-') => self.advance_twice_with(Punctuation::MinusMinus),
+            ('-', '=') => self.advance_twice_with(Punctuation::MinusEq),
+            ('-', _) => self.advance_with(Punctuation::Minus),
+            ('/', '/') => self.next_doc_comment_token(),
+            ('/', '=') => self.advance_twice_with(Punctuation::SlashEq),
+            ('?', '?') if self.peek_third() == '=' => {
+                self.advance_thrice_with(Punctuation::QuestionQuestionEq)
+            }
+            ('&', '&') if self.peek_third() == '=' => {
+                self.advance_thrice_with(Punctuation::AmpersandAmpersandEq)
+            }
+            ('|', '|') if self.peek_third() == '=' => {
+                self.advance_thrice_with(Punctuation::PipePipeEq)
+            }
+            ('?', '.') => self.advance_twice_with(Punctuation::QuestionDot),
+            ('*', '=') => self.advance_twice_with(Punctuation::StarEq),
+            ('*', '*') => self.advance_twice_with(Punctuation::StarStar),
+            ('*', _) => self.advance_with(Punctuation::Star),
+            ('/', _) => self.advance_with(Punctuation::Slash),
+            ('(', _) => self.advance_with(Punctuation::OpenParent),
+            (')', _) => self.advance_with(Punctuation::CloseParent),
+            ('[', _) => self.advance_with(Punctuation::OpenBracket),
+            (']', _) => self.advance_with(Punctuation::CloseBracket),
+            ('{', _) => self.advance_with(Punctuation::OpenBrace),
+            ('}', _) => self.advance_with(Punctuation::CloseBrace),
+            (';', _) => self.advance_with(Punctuation::Semicolon),
+            (',', _) => self.advance_with(Punctuation::Comma),
+            ('.', '.') => self.advance_twice_with(Punctuation::DotDot),
+            ('.', _) => self.advance_with(Punctuation::Dot),
+            ('=', _) => self.advance_with(Punctuation::Eq),
+            ('^', _) => self.advance_with(Punctuation::Caret),
+            (':', ':') => self.advance_twice_with(Punctuation::ColonColon),
+            (':', _) => self.advance_with(Punctuation::Colon),
+            ('!', _) => self.advance_with(Punctuation::Bang),
+            ('#', _) if self.at_line_start() => self.next_directive_token(),
+            ('b', '"') => self.next_byte_string_token(),
+            ('"', '"') if self.at_triple_quote() => self.next_block_string_token(),
+            ('"', _) => self.next_string_token(),
+            ('\'', _) => self.next_char_token(),
+            (_, _) => {
+                if is_id_start(self.current) || (self.emoji_identifiers && is_emoji(self.current)) {
+                    self.next_identifier_or_keyword_token()
+                } else if self.current.is_ascii_digit() {
+                    self.next_number_token()
+                } else {
+                    self.advance_with(RawToken::UnexpectedChar(self.current))
+                }
+            }
+        })
+    }
+}
+
+pub fn is_whitespace(c: char) -> bool {
+    matches!(
+        c,
+        // Usual ASCII suspects
+        '\u{0009}'   // \t
+        | '\u{000A}' // \n
+        | '\u{000B}' // vertical tab
+        | '\u{000C}' // form feed
+        | '\u{000D}' // \r
+        | '\u{0020}' // space
+
+        // NEXT LINE from latin1
+        | '\u{0085}'
+
+        // Bidi markers
+        | '\u{200E}' // LEFT-TO-RIGHT MARK
+        | '\u{200F}' // RIGHT-TO-LEFT MARK
+
+        // Dedicated whitespace characters from Unicode
+        | '\u{2028}' // LINE SEPARATOR
+        | '\u{2029}' // PARAGRAPH SEPARATOR
+    )
+}
+
+/// Drops the newline right after the opening `"""` and, when the closing
+/// `"""` sits on its own line, removes its indentation from every line.
+fn strip_block_indentation(content: &str) -> String {
+    let content = content.strip_prefix('\n').unwrap_or(content);
+
+    match content.rsplit_once('\n') {
+        Some((body, indentation)) if indentation.chars().all(is_whitespace) => body
+            .split('\n')
+            .map(|line| line.strip_prefix(indentation).unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => content.to_owned(),
+    }
+}
+
+/// `digits` must be valid in `radix`, apart from `_` separators, so the
+/// only way parsing them can fail is by overflowing.
+fn integer_literal(digits: &str, radix: u32, source_text: &str) -> RawToken {
+    match u64::from_str_radix(&digits.replace('_', ""), radix) {
+        Ok(value) => RawToken::IntegerLiteral {
+            value,
+            source_text: source_text.to_owned(),
+        },
+        Err(..) => RawToken::Error(LexError::IntegerOverflow),
+    }
+}
+
+/// A decimal digit or a `_` separator, which may follow the first digit.
+fn is_decimal_digit(c: char) -> bool {
+    c.is_ascii_digit() || c == '_'
+}
+
+fn is_id_start(c: char) -> bool {
+    c == '_' || unicode_xid::UnicodeXID::is_xid_start(c)
+}
+
+fn is_id_continue(c: char) -> bool {
+    unicode_xid::UnicodeXID::is_xid_continue(c)
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2600}'..='\u{27BF}' | '\u{1F000}'..='\u{1FAFF}'
+    )
+}
+
+/// Emoji and the code points that join or modify them within a single
+/// emoji sequence.
+fn is_emoji_continue(c: char) -> bool {
+    is_emoji(c)
+        || matches!(
+            c,
+            // zero width joiner
+            '\u{200D}'
+            // variation selectors
+            | '\u{FE00}'..='\u{FE0F}'
+            // combining enclosing keycap
+            | '\u{20E3}'
+            // tags, used by subdivision flags
+            | '\u{E0020}'..='\u{E007F}'
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token::{
+        EscapeError, EscapeErrorKind, LexError, Location, Punctuation, RawToken, Token,
+    };
+
+    use super::Lexer;
+
+    #[test]
+    fn eof() {
+        let mut lexer = Lexer::new("");
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn identifier() {
+        let mut lexer = Lexer::new("test");
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("test".to_owned()),
+                location: Location { start: 0, end: 4 }
+            })
+        );
+    }
+
+    #[test]
+    fn bool() {
+        let mut lexer = Lexer::new("true false");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::BoolLiteral(true),
+                location: Location { start: 0, end: 4 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::BoolLiteral(false),
+                location: Location { start: 5, end: 10 }
+            })
+        );
+    }
+
+    #[test]
+    fn integer() {
+        let mut lexer = Lexer::new("42");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::IntegerLiteral {
+                    value: 42,
+                    source_text: "42".to_owned(),
+                },
+                location: Location { start: 0, end: 2 }
+            })
+        );
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let mut lexer = Lexer::new("123456789012345678901234567890 0x10000000000000000");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Error(LexError::IntegerOverflow),
+                location: Location { start: 0, end: 30 }
+            })
+        );
+        assert_eq!(
+            lexer.next().map(|token| token.raw),
+            Some(RawToken::Error(LexError::IntegerOverflow))
+        );
+        assert_eq!(
+            raw_tokens("18446744073709551615"),
+            vec![integer_token(u64::MAX)]
+        );
+    }
+
+    #[test]
+    fn float() {
+        let mut lexer = Lexer::new("2.75");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: float_token(2.75, "2.75"),
+                location: Location { start: 0, end: 4 }
+            })
+        );
+    }
+
+    fn raw_tokens(source: &str) -> Vec<RawToken> {
+        Lexer::new(source).map(|token| token.raw).collect()
+    }
+
+    fn float_token(value: f64, source_text: &str) -> RawToken {
+        RawToken::FloatLiteral {
+            value,
+            source_text: source_text.to_owned(),
+        }
+    }
+
+    fn integer_token(value: u64) -> RawToken {
+        RawToken::IntegerLiteral {
+            value,
+            source_text: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn integer_or_float() {
+        assert_eq!(raw_tokens("1"), vec![integer_token(1)]);
+        assert_eq!(raw_tokens("1.0"), vec![float_token(1.0, "1.0")]);
+        assert_eq!(raw_tokens("1.5"), vec![float_token(1.5, "1.5")]);
+        assert_eq!(raw_tokens("0.25"), vec![float_token(0.25, "0.25")]);
+    }
+
+    #[test]
+    fn float_exponents() {
+        assert_eq!(raw_tokens("1e10"), vec![float_token(1e10, "1e10")]);
+        assert_eq!(raw_tokens("2.5E-3"), vec![float_token(2.5e-3, "2.5E-3")]);
+        assert_eq!(raw_tokens("1e+2"), vec![float_token(100.0, "1e+2")]);
+        assert_eq!(
+            raw_tokens("1e"),
+            vec![RawToken::Error(LexError::TrailingCharactersAfterNumber)]
+        );
+    }
+
+    #[test]
+    fn floats_display_as_written() {
+        for source in ["1.0", "1e10", "0.10"] {
+            assert_eq!(
+                Lexer::new(source).next().map(|token| token.raw.to_string()),
+                Some(source.to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn dot_without_fraction_digits() {
+        assert_eq!(
+            raw_tokens("5."),
+            vec![integer_token(5), RawToken::Punctuation(Punctuation::Dot)]
+        );
+        assert_eq!(
+            raw_tokens("1.field"),
+            vec![
+                integer_token(1),
+                RawToken::Punctuation(Punctuation::Dot),
+                RawToken::Identifier("field".to_owned()),
+            ]
+        );
+        assert_eq!(
+            raw_tokens("x.field"),
+            vec![
+                RawToken::Identifier("x".to_owned()),
+                RawToken::Punctuation(Punctuation::Dot),
+                RawToken::Identifier("field".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn radix_integers() {
+        for (source, value) in [("0xFF", 0xFF), ("0o17", 0o17), ("0b1010", 0b1010), ("0", 0)] {
+            assert_eq!(
+                raw_tokens(source),
+                vec![RawToken::IntegerLiteral {
+                    value,
+                    source_text: source.to_owned(),
+                }],
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn digit_separators() {
+        for (source, value) in [("1_000", 1000), ("0xFF_00", 0xFF00), ("0b1_0", 2)] {
+            assert_eq!(
+                raw_tokens(source),
+                vec![RawToken::IntegerLiteral {
+                    value,
+                    source_text: source.to_owned(),
+                }],
+                "{source}"
+            );
+        }
+
+        assert_eq!(raw_tokens("1_000.5"), vec![float_token(1000.5, "1_000.5")]);
+        assert_eq!(
+            raw_tokens("0x_"),
+            vec![RawToken::Error(LexError::MissingDigits)]
+        );
+        assert_eq!(
+            raw_tokens("_1"),
+            vec![RawToken::Identifier("_1".to_owned())]
+        );
+    }
+
+    #[test]
+    fn invalid_radix_digit() {
+        assert_eq!(
+            Lexer::new("0b1012 a").next(),
+            Some(Token {
+                raw: RawToken::Error(LexError::InvalidDigit {
+                    digit: '2',
+                    radix: 2,
+                    location: Location { start: 5, end: 6 },
+                }),
+                location: Location { start: 0, end: 6 }
+            })
+        );
+        assert_eq!(
+            raw_tokens("0xG;"),
+            vec![
+                RawToken::Error(LexError::InvalidDigit {
+                    digit: 'G',
+                    radix: 16,
+                    location: Location { start: 2, end: 3 },
+                }),
+                RawToken::Punctuation(Punctuation::Semicolon),
+            ]
+        );
+        assert_eq!(
+            raw_tokens("0o"),
+            vec![RawToken::Error(LexError::MissingDigits)]
+        );
+    }
+
+    #[test]
+    fn multiple_decimal_points() {
+        let mut lexer = Lexer::new("1.2.3;");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Error(LexError::MultipleDecimalPoints),
+                location: Location { start: 0, end: 5 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Punctuation(Punctuation::Semicolon),
+                location: Location { start: 5, end: 6 }
+            })
+        );
+    }
+
+    #[test]
+    fn trailing_characters_after_number() {
+        let mut lexer = Lexer::new("10px 10 px");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Error(LexError::TrailingCharactersAfterNumber),
+                location: Location { start: 0, end: 4 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::IntegerLiteral {
+                    value: 10,
+                    source_text: "10".to_owned()
+                },
+                location: Location { start: 5, end: 7 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("px".to_owned()),
+                location: Location { start: 8, end: 10 }
+            })
+        );
+    }
+
+    #[test]
+    fn block_string() {
+        let mut lexer = Lexer::new(r#""""say "hi" twice""""#);
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::StringLiteral("say \"hi\" twice".to_owned()),
+                location: Location { start: 0, end: 20 }
+            })
+        );
+    }
+
+    #[test]
+    fn block_string_indentation() {
+        let mut lexer = Lexer::new(
+            "\"\"\"
+        first
+          second
+        \"\"\"",
+        );
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::StringLiteral("first\n  second".to_owned()),
+                location: Location { start: 0, end: 46 }
+            })
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn string() {
+        let mut lexer = Lexer::new(r#""a\tb\\" c"#);
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::StringLiteral("a\tb\\".to_owned()),
+                location: Location { start: 0, end: 8 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("c".to_owned()),
+                location: Location { start: 9, end: 10 }
+            })
+        );
+    }
+
+    #[test]
+    fn string_spans_both_quotes() {
+        let mut lexer = Lexer::new(r#""hello" "" x"#);
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::StringLiteral("hello".to_owned()),
+                location: Location { start: 0, end: 7 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::StringLiteral(String::new()),
+                location: Location { start: 8, end: 10 }
+            })
+        );
+        assert_eq!(
+            lexer.next().map(|token| token.location),
+            Some(Location { start: 11, end: 12 })
+        );
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let mut lexer = Lexer::new("a \"hello");
+
+        lexer.next();
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Error(LexError::UnterminatedString),
+                location: Location { start: 2, end: 8 }
+            })
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn standard_escapes() {
+        let mut lexer = Lexer::new(r#""line\n\t\r\\\"a\"\0" x"#);
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::StringLiteral("line\n\t\r\\\"a\"\0".to_owned()),
+                location: Location { start: 0, end: 21 }
+            })
+        );
+        assert_eq!(
+            lexer.next().map(|token| token.location),
+            Some(Location { start: 22, end: 23 })
+        );
+    }
+
+    #[test]
+    fn unknown_escape() {
+        assert_eq!(
+            escape_error(r#""ab\q""#),
+            EscapeError {
+                kind: EscapeErrorKind::Unknown('q'),
+                location: Location { start: 3, end: 5 },
+            }
+        );
+    }
+
+    #[test]
+    fn byte_string() {
+        let mut lexer = Lexer::new(r#"b"\x00\xFFa\n" b "b""#);
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::ByteStringLiteral(vec![0x00, 0xFF, b'a', b'\n']),
+                location: Location { start: 0, end: 14 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("b".to_owned()),
+                location: Location { start: 15, end: 16 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::StringLiteral("b".to_owned()),
+                location: Location { start: 17, end: 20 }
+            })
+        );
+    }
+
+    #[test]
+    fn malformed_byte_escape() {
+        assert_eq!(
+            escape_error(r#"b"\xF""#),
+            EscapeError {
+                kind: EscapeErrorKind::MalformedHex,
+                location: Location { start: 2, end: 4 },
+            }
+        );
+    }
+
+    #[test]
+    fn char_literals() {
+        assert_eq!(
+            Lexer::new("'a' '9' 'é' '\\n'").collect::<Vec<_>>(),
+            vec![
+                Token {
+                    raw: RawToken::CharLiteral('a'),
+                    location: Location { start: 0, end: 3 },
+                },
+                Token {
+                    raw: RawToken::CharLiteral('9'),
+                    location: Location { start: 4, end: 7 },
+                },
+                Token {
+                    raw: RawToken::CharLiteral('é'),
+                    location: Location { start: 8, end: 12 },
+                },
+                Token {
+                    raw: RawToken::CharLiteral('\n'),
+                    location: Location { start: 13, end: 17 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_char_literals() {
+        assert_eq!(raw_tokens("''"), vec![RawToken::Error(LexError::EmptyChar)]);
+        assert_eq!(
+            Lexer::new("'a").next(),
+            Some(Token {
+                raw: RawToken::Error(LexError::UnterminatedChar),
+                location: Location { start: 0, end: 2 },
+            })
+        );
+        assert_eq!(
+            raw_tokens("'\\"),
+            vec![RawToken::Error(LexError::UnterminatedChar)]
+        );
+        assert_eq!(escape_error("'\\q'").kind, EscapeErrorKind::Unknown('q'));
+    }
+
+    #[test]
+    fn multiple_chars_in_char_literal() {
+        assert_eq!(
+            Lexer::new("'ab' x").collect::<Vec<_>>(),
+            vec![
+                Token {
+                    raw: RawToken::Error(LexError::MultipleCharsInChar),
+                    location: Location { start: 0, end: 4 },
+                },
+                Token {
+                    raw: RawToken::Identifier("x".to_owned()),
+                    location: Location { start: 5, end: 6 },
+                },
+            ]
+        );
+        assert_eq!(
+            raw_tokens("'ab\n'"),
+            vec![
+                RawToken::Error(LexError::UnterminatedChar),
+                RawToken::Identifier("b".to_owned()),
+                RawToken::Error(LexError::UnterminatedChar)
+            ]
+        );
+    }
+
+    #[test]
+    fn char_offsets_skip_escapes() {
+        let mut lexer = Lexer::new(r#"x "a\tb\u{e9}c""#).with_char_offsets();
+
+        lexer.next();
+        assert_eq!(lexer.char_offsets(), Some(&[][..]));
+
+        assert_eq!(
+            lexer.next().map(|token| token.raw),
+            Some(RawToken::StringLiteral("a\tbéc".to_owned()))
+        );
+        assert_eq!(lexer.char_offsets(), Some(&[3, 4, 6, 7, 13][..]));
+    }
+
+    #[test]
+    fn char_offsets_are_opt_in() {
+        let mut lexer = Lexer::new(r#""a""#);
+
+        lexer.next();
+        assert_eq!(lexer.char_offsets(), None);
+    }
+
+    fn escape_error(source: &str) -> EscapeError {
+        match Lexer::new(source).next().map(|token| token.raw) {
+            Some(RawToken::Error(LexError::InvalidEscape(error))) => error,
+            other => panic!("expected an escape error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn valid_escapes() {
+        let mut lexer = Lexer::new(r#""\x41\x7f\u{1F680}\u{0}\u{10FFFF}\u{00e9}""#);
+
+        assert_eq!(
+            lexer.next().map(|token| token.raw),
+            Some(RawToken::StringLiteral("A\u{7f}🚀\0\u{10FFFF}é".to_owned()))
+        );
+    }
+
+    #[test]
+    fn surrogate_escape() {
+        assert_eq!(
+            escape_error(r#""ab\u{D800}""#),
+            EscapeError {
+                kind: EscapeErrorKind::Surrogate(0xD800),
+                location: Location { start: 3, end: 11 },
+            }
+        );
+    }
+
+    #[test]
+    fn out_of_range_escape() {
+        assert_eq!(
+            escape_error(r#""\u{110000}""#),
+            EscapeError {
+                kind: EscapeErrorKind::OutOfRange(0x11_0000),
+                location: Location { start: 1, end: 11 },
+            }
+        );
+    }
+
+    #[test]
+    fn non_ascii_hex_escape() {
+        assert_eq!(
+            escape_error(r#""\x80""#),
+            EscapeError {
+                kind: EscapeErrorKind::NonAsciiHex(0x80),
+                location: Location { start: 1, end: 5 },
+            }
+        );
+        assert_eq!(
+            escape_error(r#""\x80""#).to_string(),
+            "invalid escape: `\\x80` is out of the ASCII range, use `\\u{80}`"
+        );
+    }
+
+    #[test]
+    fn malformed_escapes() {
+        assert_eq!(escape_error(r#""\x4""#).kind, EscapeErrorKind::MalformedHex);
+        assert_eq!(
+            escape_error(r#""\u1234""#).kind,
+            EscapeErrorKind::MalformedUnicode
+        );
+        assert_eq!(
+            escape_error(r#""\u{}""#).kind,
+            EscapeErrorKind::MalformedUnicode
+        );
+        assert_eq!(
+            escape_error(r#""\u{1234567}""#).kind,
+            EscapeErrorKind::MalformedUnicode
+        );
+    }
+
+    #[test]
+    fn invalid_escape_keeps_lexing() {
+        let mut lexer = Lexer::new(r#""\u{D800}" a"#);
+
+        lexer.next();
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("a".to_owned()),
+                location: Location { start: 11, end: 12 }
+            })
+        );
+    }
+
+    #[test]
+    fn custom_escape() {
+        let mut lexer = Lexer::new(r#""\e[1m\n""#).with_escape('e', "\u{1b}");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::StringLiteral("\u{1b}[1m\n".to_owned()),
+                location: Location { start: 0, end: 9 }
+            })
+        );
+    }
+
+    #[test]
+    fn punctuation() {
+        let mut lexer = Lexer::new("+");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Punctuation(Punctuation::Plus),
+                location: Location { start: 0, end: 1 }
+            })
+        );
+    }
+
+    #[test]
+    fn line_comment() {
+        let mut lexer = Lexer::new("// comment\ntest");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("test".to_owned()),
+                location: Location { start: 11, end: 15 }
+            })
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn comment_between_tokens() {
+        let mut lexer = Lexer::new("1 // hi\n + 2");
+
+        assert_eq!(lexer.next().map(|token| token.raw), Some(integer_token(1)));
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Punctuation(Punctuation::Plus),
+                location: Location { start: 9, end: 10 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: integer_token(2),
+                location: Location { start: 11, end: 12 }
+            })
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn comment_at_eof() {
+        assert_eq!(
+            raw_tokens("a // no newline"),
+            vec![RawToken::Identifier("a".to_owned())]
+        );
+        assert_eq!(raw_tokens("//"), vec![]);
+    }
+
+    #[test]
+    fn block_comment() {
+        let mut lexer = Lexer::new("a /* one\ntwo */ b");
+
+        lexer.next();
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("b".to_owned()),
+                location: Location { start: 16, end: 17 }
+            })
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn nested_block_comment() {
+        let mut lexer = Lexer::new("/* outer /* inner */ still comment */ a /**/");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("a".to_owned()),
+                location: Location { start: 38, end: 39 }
+            })
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn unterminated_block_comment() {
+        let mut lexer = Lexer::new("a /* /* */ b");
+
+        lexer.next();
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Error(LexError::UnterminatedBlockComment),
+                location: Location { start: 2, end: 12 }
+            })
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn four_slashes_are_a_plain_comment() {
+        assert_eq!(
+            raw_tokens("//// banner\na"),
+            vec![RawToken::Identifier("a".to_owned())]
+        );
+    }
+
+    #[test]
+    fn doc_comment() {
+        let mut lexer = Lexer::new("/// Docs.\ntest");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::DocComment("Docs.".to_owned()),
+                location: Location { start: 0, end: 9 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("test".to_owned()),
+                location: Location { start: 10, end: 14 }
+            })
+        );
+    }
+
+    #[test]
+    fn module_doc_comment_only_in_header() {
+        let mut lexer = Lexer::new("//! Docs.\na //! not docs");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::ModuleDocComment("Docs.".to_owned()),
+                location: Location { start: 0, end: 9 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("a".to_owned()),
+                location: Location { start: 10, end: 11 }
+            })
+        );
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn emoji_identifiers() {
+        let mut lexer = Lexer::new("var 🚀 = 👨\u{200D}👩\u{200D}👧x;").with_emoji_identifiers();
+
+        lexer.next();
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("🚀".to_owned()),
+                location: Location { start: 4, end: 8 }
+            })
+        );
+
+        lexer.next();
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("👨\u{200D}👩\u{200D}👧x".to_owned()),
+                location: Location { start: 11, end: 30 }
+            })
+        );
+        assert_eq!(
+            Lexer::collect_errors("🚀"),
+            vec![Token {
+                raw: RawToken::UnexpectedChar('🚀'),
+                location: Location { start: 0, end: 4 }
+            }]
+        );
+    }
+
+    #[test]
+    fn directive() {
+        let mut lexer = Lexer::new("a\n#if DEBUG\nb");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("a".to_owned()),
+                location: Location { start: 0, end: 1 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Directive("if DEBUG".to_owned()),
+                location: Location { start: 2, end: 11 }
+            })
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("b".to_owned()),
+                location: Location { start: 12, end: 13 }
+            })
+        );
+    }
+
+    #[test]
+    fn directive_only_at_line_start() {
+        assert_eq!(
+            raw_tokens("a # b\n  #endif"),
+            vec![
+                RawToken::Identifier("a".to_owned()),
+                RawToken::UnexpectedChar('#'),
+                RawToken::Identifier("b".to_owned()),
+                RawToken::Directive("endif".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn shebang() {
+        let mut lexer = Lexer::new("#!/usr/bin/env spectra\n//! Docs.\na");
+
+        assert_eq!(
+            lexer.next().map(|token| token.raw),
+            Some(RawToken::ModuleDocComment("Docs.".to_owned()))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Token {
+                raw: RawToken::Identifier("a".to_owned()),
+                location: Location { start: 33, end: 34 }
+            })
+        );
+        assert_eq!(
+            raw_tokens("a\n#!b"),
+            vec![
+                RawToken::Identifier("a".to_owned()),
+                RawToken::Directive("!b".to_owned()),
+            ]
+        );
+    }
+
+    /// Checks that `tokens` are in bounds, in order, don't overlap, and leave
+    /// only whitespace and `//` comments uncovered. Each token's text must
+    /// also lex back into that same token on its own.
+    fn assert_contiguous(tokens: &[Token], source: &str) {
+        let mut covered = 0;
+
+        for token in tokens {
+            let Location { start, end } = token.location;
+
+            assert!(
+                start < end && end <= source.len(),
+                "{token:?} is out of bounds"
+            );
+            assert!(covered <= start, "{token:?} overlaps the previous token");
+
+            let gap = &source[covered..start];
+            let trivia = gap
+                .lines()
+                .map(|line| line.split_once("//").map_or(line, |(code, _)| code))
+                .all(|code| code.trim().is_empty());
+
+            assert!(trivia, "{gap:?} before {token:?} isn't covered by a token");
+            assert_eq!(
+                Lexer::new(&source[start..end])
+                    .map(|token| token.raw)
+                    .collect::<Vec<_>>(),
+                vec![token.raw.clone()],
+                "{token:?} doesn't span its own text"
+            );
+
+            covered = end;
+        }
+
+        assert!(
+            source[covered..].trim().is_empty(),
+            "trailing source isn't covered"
+        );
+    }
+
+    #[test]
+    fn tokens_are_contiguous() {
+        let source = r#"//! Module docs.
+/// Docs.
+pub var (a, b): (Int, Int) = f(x: 1.5, y: 2) ** 3 ^ 4; // trailing comment
+#if DEBUG
+a.b[0] += -c!; a -= --b; c *= d++; e /= "s\n"; g ??= h?.i;
+j &&= k::l?.[0]; m ||= typeof n;
+do { break 1; continue; } while true;
+switch x { case 1 {} default {} }
+var [first, ..rest] = """x""";
+"#;
+
+        assert_contiguous(&Lexer::new(source).collect::<Vec<_>>(), source);
+    }
+
+    #[test]
+    fn remaining() {
+        let mut lexer = Lexer::new("var a = 1; b");
+
+        assert_eq!(lexer.remaining(), "var a = 1; b");
+
+        lexer.next();
+        lexer.next();
+
+        assert_eq!(lexer.remaining(), " = 1; b");
+
+        lexer.by_ref().for_each(drop);
+
+        assert_eq!(lexer.remaining(), "");
+    }
+
+    #[test]
+    fn skip_to_char() {
+        let mut lexer = Lexer::new("a $ § b; c");
+
+        assert_eq!(lexer.skip_to_char(';'), "a $ § b");
+        assert_eq!(lexer.remaining(), "; c");
+        assert_eq!(lexer.skip_to_char('!'), "; c");
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn collect_errors() {
+        assert_eq!(
+            Lexer::collect_errors("a $ b § 1.2.3 'x \"abc"),
+            vec![
+                Token {
+                    raw: RawToken::UnexpectedChar('$'),
+                    location: Location { start: 2, end: 3 }
+                },
+                Token {
+                    raw: RawToken::UnexpectedChar('§'),
+                    location: Location { start: 6, end: 8 }
+                },
+                Token {
+                    raw: RawToken::Error(LexError::MultipleDecimalPoints),
+                    location: Location { start: 9, end: 14 }
+                },
+                Token {
+                    raw: RawToken::Error(LexError::UnterminatedChar),
+                    location: Location { start: 15, end: 17 }
+                },
+                Token {
+                    raw: RawToken::Error(LexError::UnterminatedString),
+                    location: Location { start: 18, end: 22 }
+                },
+            ]
         );
     }
 }
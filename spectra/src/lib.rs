@@ -0,0 +1,29 @@
+//! Lexer, parser and AST for Spectra. Only `alloc` is required when the
+//! `std` feature is disabled.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+pub mod ast;
+pub mod ast_eq;
+pub mod completion;
+pub mod complexity;
+pub mod const_eval;
+pub mod diff;
+pub mod events;
+pub mod lexer;
+pub mod lint;
+#[cfg(feature = "std")]
+pub mod metrics;
+pub mod parser;
+pub mod rename;
+pub mod resolver;
+#[cfg(feature = "std")]
+pub mod streaming_lexer;
+pub mod token;
+pub mod token_stream;
+pub mod validate;
+pub mod visit;
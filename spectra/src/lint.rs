@@ -0,0 +1,164 @@
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::{self, Display};
+
+use crate::{
+    ast::Module,
+    resolver::{resolve, BindingKind},
+    token::Location,
+};
+
+/// Names the runtime is going to provide. Binding one of them still works,
+/// but hides the builtin for the rest of the scope.
+pub const BUILTINS: &[&str] = &["print", "len"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub message: String,
+    pub location: Location,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Opt-in lint: reports every `var` binding that shadows one of
+/// [`BUILTINS`]. The warning points at the whole `var` statement.
+#[must_use]
+pub fn shadowed_builtins(module: &Module) -> Vec<Warning> {
+    resolve(module)
+        .into_iter()
+        .filter_map(|binding| match binding.kind {
+            BindingKind::Var { statement }
+                if BUILTINS.contains(&binding.identifier.identifier.as_str()) =>
+            {
+                Some(Warning {
+                    message: format!(
+                        "binding shadows builtin `{}`",
+                        binding.identifier.identifier
+                    ),
+                    location: statement,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Opt-in lint: reports every `var` binding that is never mentioned again
+/// in its scope. Names starting with `_` are meant to be unused and are
+/// exempt, as are function parameters.
+#[must_use]
+pub fn unused_variables(module: &Module) -> Vec<Warning> {
+    resolve(module)
+        .into_iter()
+        .filter(|binding| {
+            matches!(binding.kind, BindingKind::Var { .. })
+                && !binding.used
+                && !binding.identifier.identifier.starts_with('_')
+        })
+        .map(|binding| Warning {
+            message: format!("unused variable `{}`", binding.identifier.identifier),
+            location: binding.identifier.location,
+        })
+        .collect()
+}
+
+/// Opt-in lint: reports lines whose leading indentation mixes tabs and
+/// spaces, pointing at the indentation. Lines indented with only tabs or
+/// only spaces are fine, whichever style the rest of the file uses.
+///
+/// Works on the raw source, so indentation inside block strings is checked
+/// too.
+#[must_use]
+pub fn mixed_indentation(source: &str) -> Vec<Warning> {
+    let mut warnings = vec![];
+    let mut line_start = 0;
+
+    for line in source.split_inclusive('\n') {
+        let indentation = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let indentation_text = &line[..indentation];
+
+        if indentation_text.contains(' ') && indentation_text.contains('\t') {
+            warnings.push(Warning {
+                message: "indentation mixes tabs and spaces".into(),
+                location: Location {
+                    start: line_start,
+                    end: line_start + indentation,
+                },
+            });
+        }
+
+        line_start += line.len();
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parser::Parser, token::Location};
+
+    use super::{mixed_indentation, shadowed_builtins, unused_variables, Warning};
+
+    #[test]
+    fn shadowed_print() {
+        let module = Parser::new("var print = 1; var x = 2;").parse().unwrap();
+
+        assert_eq!(
+            shadowed_builtins(&module),
+            vec![Warning {
+                message: "binding shadows builtin `print`".to_owned(),
+                location: Location { start: 0, end: 14 },
+            }]
+        );
+    }
+
+    #[test]
+    fn unused_variable() {
+        let module = Parser::new("var unused = 1;").parse().unwrap();
+
+        assert_eq!(
+            unused_variables(&module),
+            vec![Warning {
+                message: "unused variable `unused`".to_owned(),
+                location: Location { start: 4, end: 10 },
+            }]
+        );
+    }
+
+    #[test]
+    fn underscore_prefixed_variable() {
+        let module = Parser::new("var _unused = 1;").parse().unwrap();
+
+        assert_eq!(unused_variables(&module), vec![]);
+    }
+
+    #[test]
+    fn used_variables() {
+        let module = Parser::new("var a = 1; var f = fun (x) { var b = a; b + x; }; f(2);")
+            .parse()
+            .unwrap();
+
+        assert_eq!(unused_variables(&module), vec![]);
+    }
+
+    #[test]
+    fn only_mixed_lines_are_flagged() {
+        let source = "var f = fun () {\n\tvar a = 1;\n\t  a\n    };\n";
+
+        assert_eq!(
+            mixed_indentation(source),
+            vec![Warning {
+                message: "indentation mixes tabs and spaces".to_owned(),
+                location: Location { start: 29, end: 32 },
+            }]
+        );
+    }
+
+    #[test]
+    fn consistent_indentation() {
+        assert_eq!(mixed_indentation("a;\n\t\tb;\n    c;"), vec![]);
+    }
+}
@@ -1,16 +1,14 @@
 use std::fs;
 
-use parser::Parser;
-
-mod ast;
-mod lexer;
-mod parser;
-mod token;
+use spectra::parser::Parser;
 
 fn main() {
     let filepath = std::env::args().nth(1).expect("no filepath given");
 
     let contents = fs::read_to_string(filepath).unwrap();
     let mut parser = Parser::new(&contents);
-    println!("{:?}", parser.parse());
+    match parser.parse() {
+        Ok(module) => println!("{:?}", module),
+        Err(error) => eprintln!("error: {}", error),
+    }
 }
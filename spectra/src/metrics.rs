@@ -0,0 +1,100 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    ast::{Expression, Module, Statement},
+    parser::{ParseResult, Parser},
+    visit::{self, Visitor},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseMetrics {
+    /// Tokens the parser consumed: everything but comments, keeping only
+    /// doc comments that document a statement.
+    pub token_count: usize,
+    /// Statements and expressions in the resulting module.
+    pub node_count: usize,
+    /// Deepest statement/expression nesting; a top-level statement is at
+    /// depth 1.
+    pub max_depth: usize,
+    /// Time spent in [`Parser::parse`] alone.
+    pub elapsed: Duration,
+}
+
+/// Parses `source` like [`Parser::parse`] and reports what it took.
+pub fn parse_with_metrics(source: &str) -> ParseResult<(Module, ParseMetrics)> {
+    let mut parser = Parser::new(source);
+
+    let start = Instant::now();
+    let module = parser.parse()?;
+    let elapsed = start.elapsed();
+
+    let mut counter = NodeCounter {
+        depth: 0,
+        node_count: 0,
+        max_depth: 0,
+    };
+
+    for statement in &module.statements {
+        counter.visit_statement(statement);
+    }
+
+    let metrics = ParseMetrics {
+        token_count: parser.tokens_consumed(),
+        node_count: counter.node_count,
+        max_depth: counter.max_depth,
+        elapsed,
+    };
+
+    Ok((module, metrics))
+}
+
+struct NodeCounter {
+    depth: usize,
+    node_count: usize,
+    max_depth: usize,
+}
+
+impl NodeCounter {
+    fn enter(&mut self) {
+        self.depth += 1;
+        self.node_count += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+    }
+}
+
+impl Visitor for NodeCounter {
+    fn visit_statement(&mut self, statement: &Statement) {
+        self.enter();
+        visit::walk_statement(self, statement);
+        self.depth -= 1;
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        self.enter();
+        visit::walk_expression(self, expression);
+        self.depth -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_with_metrics;
+
+    #[test]
+    fn counts() {
+        let (module, metrics) = parse_with_metrics("var x = 1 + 2; x;").unwrap();
+
+        assert_eq!(module.statements.len(), 2);
+        assert_eq!(metrics.token_count, 9);
+        assert_eq!(metrics.node_count, 6);
+        assert_eq!(metrics.max_depth, 3);
+    }
+
+    #[test]
+    fn comments_are_not_tokens() {
+        let (_, metrics) =
+            parse_with_metrics("/// Docs.\nvar x = 1 /* one */ + /// two\n 2; // end").unwrap();
+
+        assert_eq!(metrics.token_count, 8);
+    }
+}
@@ -1,12 +1,151 @@
 use crate::{
-    ast::{Expression, IdentifierAST, Literal, Module, RawLiteral, Statement, StatementsBlock},
+    ast::{
+        Argument, EnumVariant, Expression, IdentifierAST, Literal, MethodSignature, Module,
+        Pattern, RawLiteral, Statement, StatementsBlock, SwitchCase, TypeAST, TypeParameter,
+        VarKind, Visibility,
+    },
     lexer::Lexer,
     token::{Keyword, Location, Precedence, Punctuation, RawToken, Token},
 };
-use std::iter::Peekable;
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
 
 pub struct Parser<'s> {
-    lexer: Peekable<Lexer<'s>>,
+    lexer: Tokens<'s>,
+    options: ParserOptions,
+    precedences: PrecedenceTable,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Parse `^` as exponentiation (like `**`) instead of bitwise XOR.
+    pub caret_is_power: bool,
+    /// Keep parenthesized expressions as [`Expression::Grouping`] nodes.
+    pub preserve_parens: bool,
+    /// Parse `a, b` as an [`Expression::Comma`] in expression statements,
+    /// block tails and parentheses. Elsewhere, like in call arguments, a
+    /// comma still separates expressions.
+    pub comma_expressions: bool,
+}
+
+/// Per-operator precedence overrides, for dialects that group operators
+/// differently. Operators without an override keep the built-in precedence
+/// from `From<Punctuation> for Precedence`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PrecedenceTable {
+    overrides: Vec<(Punctuation, Precedence)>,
+}
+
+impl PrecedenceTable {
+    #[must_use]
+    pub fn with(mut self, punctuation: Punctuation, precedence: Precedence) -> Self {
+        self.overrides
+            .retain(|(overridden, _)| *overridden != punctuation);
+        self.overrides.push((punctuation, precedence));
+        self
+    }
+
+    #[must_use]
+    pub fn get(&self, punctuation: Punctuation) -> Option<Precedence> {
+        self.overrides
+            .iter()
+            .find(|(overridden, _)| *overridden == punctuation)
+            .map(|(_, precedence)| *precedence)
+    }
+}
+
+/// The lexer's tokens, minus doc comments that can't document a statement:
+/// those inside an expression or argument list, and those with no statement
+/// after them. Those are ignored like plain `//` comments.
+#[derive(Clone)]
+struct Tokens<'s> {
+    lexer: Lexer<'s>,
+    peeked: Option<Option<Token>>,
+    /// Whether the last token could end a statement or open a block.
+    at_statement_start: bool,
+    /// Tokens handed to the parser so far, not counting a peeked one.
+    consumed: usize,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = match self.peeked.take() {
+            Some(token) => token,
+            None => self.lex(),
+        };
+
+        if token.is_some() {
+            self.consumed += 1;
+        }
+
+        token
+    }
+}
+
+impl Tokens<'_> {
+    fn peek(&mut self) -> Option<&Token> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.lex());
+        }
+
+        self.peeked.as_ref().and_then(Option::as_ref)
+    }
+
+    fn next_if(&mut self, f: impl FnOnce(&Token) -> bool) -> Option<Token> {
+        if self.peek().is_some_and(f) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    fn lex(&mut self) -> Option<Token> {
+        loop {
+            let token = self.lexer.next()?;
+
+            if matches!(token.raw, RawToken::DocComment(..))
+                && !(self.at_statement_start && self.statement_follows())
+            {
+                continue;
+            }
+
+            self.at_statement_start = matches!(
+                token.raw,
+                RawToken::DocComment(..)
+                    | RawToken::ModuleDocComment(..)
+                    | RawToken::Punctuation(
+                        Punctuation::Semicolon | Punctuation::OpenBrace | Punctuation::CloseBrace
+                    )
+            );
+
+            return Some(token);
+        }
+    }
+
+    /// Whether a statement starts after the doc comments at the front of the
+    /// stream, rather than the end of a block, a `switch` case or the `while`
+    /// of a `do ... while`.
+    fn statement_follows(&self) -> bool {
+        self.lexer
+            .clone()
+            .find(|token| !matches!(token.raw, RawToken::DocComment(..)))
+            .is_some_and(|token| {
+                !matches!(
+                    token.raw,
+                    RawToken::Punctuation(Punctuation::CloseBrace)
+                        | RawToken::Keyword(Keyword::Case | Keyword::Default | Keyword::While)
+                )
+            })
+    }
 }
 
 impl<'s> Parser<'s> {
@@ -16,8 +155,87 @@ impl<'s> Parser<'s> {
 
     pub fn from(lexer: Lexer<'s>) -> Self {
         Self {
-            lexer: lexer.peekable(),
+            lexer: Tokens {
+                lexer,
+                peeked: None,
+                at_statement_start: true,
+                consumed: 0,
+            },
+            options: ParserOptions::default(),
+            precedences: PrecedenceTable::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_precedence_table(mut self, precedences: PrecedenceTable) -> Self {
+        self.precedences = precedences;
+        self
+    }
+
+    fn precedence_of(&self, raw: &RawToken) -> Precedence {
+        if let Some(precedence) = match raw {
+            RawToken::Punctuation(punctuation) => self.precedences.get(*punctuation),
+            _ => None,
+        } {
+            return precedence;
+        }
+
+        match raw {
+            RawToken::Punctuation(Punctuation::Caret) if self.options.caret_is_power => {
+                Precedence::Power
+            }
+            _ => raw.clone().into(),
+        }
+    }
+
+    fn peek_precedence(&mut self) -> Precedence {
+        match self.lexer.peek().map(|token| token.raw.clone()) {
+            Some(raw) => self.precedence_of(&raw),
+            None => Precedence::Lowest,
+        }
+    }
+
+    /// Whether the next token is a `!` that can't force-unwrap `operand`:
+    /// one separated from it by whitespace, or one followed by a token that
+    /// can only start an operand, like `a !b`. `(` and `-` don't count, since
+    /// `a!(b)` and `a! - b` still unwrap `a`.
+    fn at_detached_bang(&mut self, operand: &Expression) -> bool {
+        let Some(bang_start) = self
+            .lexer
+            .peek()
+            .filter(|token| token.raw == RawToken::from(Punctuation::Bang))
+            .map(|token| token.location.start)
+        else {
+            return false;
+        };
+
+        if bang_start != operand.location().end {
+            return true;
         }
+
+        let mut ahead = self.lexer.clone();
+        ahead.next();
+
+        ahead.peek().is_some_and(|token| {
+            matches!(
+                token.raw,
+                RawToken::Identifier(..)
+                    | RawToken::StringLiteral(..)
+                    | RawToken::ByteStringLiteral(..)
+                    | RawToken::BoolLiteral(..)
+                    | RawToken::IntegerLiteral { .. }
+                    | RawToken::FloatLiteral { .. }
+                    | RawToken::CharLiteral(..)
+                    | RawToken::Punctuation(Punctuation::Bang)
+                    | RawToken::Keyword(
+                        Keyword::New | Keyword::Fun | Keyword::Typeof | Keyword::Sizeof
+                    )
+            )
+        })
     }
 
     pub fn consume(&mut self, expected: impl Into<RawToken>) -> ParseResult<()> {
@@ -33,12 +251,14 @@ impl<'s> Parser<'s> {
                 })
             } else {
                 Err(ParseError {
+                    kind: ParseErrorKind::UnexpectedToken,
                     expected: "identifier".to_owned(),
                     got: Some(got.clone()),
                 })
             }
         } else {
             Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
                 expected: "identifier".to_owned(),
                 got: None,
             })
@@ -49,46 +269,96 @@ impl<'s> Parser<'s> {
         let expected = expected.into();
 
         if let Some(got) = self.lexer.next() {
-            if got.raw == expected.clone().into() {
+            if got.raw == expected {
                 Ok(got.clone())
             } else {
                 Err(ParseError {
+                    kind: ParseErrorKind::UnexpectedToken,
                     expected: expected.to_string(),
                     got: Some(got.clone()),
                 })
             }
         } else {
             Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
                 expected: expected.to_string(),
                 got: None,
             })
         }
     }
 
+    /// Consumes the next token if it is `expected`, and returns whether it
+    /// did. Unlike [`Parser::consume`], a mismatch leaves the token in place.
+    pub fn try_consume(&mut self, expected: impl Into<RawToken>) -> bool {
+        let expected = expected.into();
+
+        self.lexer.next_if(|token| token.raw == expected).is_some()
+    }
+
+    /// [`Parser::try_consume`] for a keyword.
+    pub fn match_keyword(&mut self, keyword: Keyword) -> bool {
+        self.try_consume(keyword)
+    }
+
+    pub fn expect_any(&mut self, options: &[RawToken]) -> ParseResult<Token> {
+        match self.lexer.next() {
+            Some(got) if options.contains(&got.raw) => Ok(got),
+            got => Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: options
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" or "),
+                got,
+            }),
+        }
+    }
+
+    /// Parses an expression, consuming only operators that bind tighter than
+    /// `precedence`. Parsing stops before the first operator at or below the
+    /// floor and leaves it in the stream, so [`Precedence::Lowest`] parses a
+    /// whole expression and e.g. [`Precedence::Assign`] stops at `=`.
+    ///
+    /// Operators of the same precedence are folded in the loop below, and the
+    /// right operand only recurses for tighter-binding operators, so recursion
+    /// depth is bounded by the number of precedence levels rather than by the
+    /// length of a left-associative chain like `a + b + ... + z`.
+    ///
+    /// The named entry points below cover the common floors.
     pub fn parse_expression(&mut self, precedence: Precedence) -> ParseResult<Expression> {
         let mut left = self.parse_primary_expression()?;
 
-        while precedence
-            < self
-                .lexer
-                .peek()
-                .map(|t| t.clone().into())
-                .unwrap_or(Precedence::Lowest)
-        {
+        while precedence < self.peek_precedence() && !self.at_detached_bang(&left) {
             left = match self.lexer.next() {
                 Some(
-                    operator @ Token {
+                    mut operator @ Token {
                         raw:
                             RawToken::Punctuation(
                                 Punctuation::Plus
                                 | Punctuation::Minus
                                 | Punctuation::Star
-                                | Punctuation::Slash,
+                                | Punctuation::StarStar
+                                | Punctuation::Slash
+                                | Punctuation::Caret,
                             ),
                         ..
                     },
                 ) => {
-                    let right = self.parse_expression(operator.raw.clone().into())?;
+                    let precedence = self.precedence_of(&operator.raw);
+
+                    // `**` is right-associative: `a ** b ** c` is `a ** (b ** c)`.
+                    let right = if precedence == Precedence::Power {
+                        self.parse_expression(Precedence::Prefix)?
+                    } else {
+                        self.parse_expression(precedence)?
+                    };
+
+                    if self.options.caret_is_power
+                        && operator.raw == RawToken::from(Punctuation::Caret)
+                    {
+                        operator.raw = Punctuation::StarStar.into();
+                    }
 
                     Expression::Binary {
                         location: Location {
@@ -113,6 +383,70 @@ impl<'s> Parser<'s> {
                     left: Box::new(left),
                     operator,
                 },
+                Some(Token {
+                    raw: RawToken::Punctuation(Punctuation::Bang),
+                    location: Location { end, .. },
+                }) => Expression::ForceUnwrap {
+                    location: Location {
+                        start: left.location().start,
+                        end,
+                    },
+                    expression: Box::new(left),
+                },
+                Some(
+                    operator @ Token {
+                        raw:
+                            RawToken::Punctuation(
+                                Punctuation::Eq
+                                | Punctuation::PlusEq
+                                | Punctuation::MinusEq
+                                | Punctuation::StarEq
+                                | Punctuation::SlashEq
+                                | Punctuation::QuestionQuestionEq
+                                | Punctuation::AmpersandAmpersandEq
+                                | Punctuation::PipePipeEq,
+                            ),
+                        ..
+                    },
+                ) => {
+                    if !left.is_assignable() {
+                        return Err(ParseError {
+                            kind: ParseErrorKind::UnexpectedToken,
+                            expected: "assignable expression".to_owned(),
+                            got: Some(operator),
+                        });
+                    }
+
+                    let value = self.parse_expression(Precedence::Lowest)?;
+
+                    Expression::Assignment {
+                        location: Location {
+                            start: left.location().start,
+                            end: value.location().end,
+                        },
+                        target: Box::new(left),
+                        operator,
+                        value: Box::new(value),
+                    }
+                }
+                Some(Token {
+                    raw: RawToken::Punctuation(Punctuation::OpenBracket),
+                    ..
+                }) => {
+                    let index = self.parse_expression(Precedence::Lowest)?;
+
+                    Expression::Index {
+                        location: Location {
+                            start: left.location().start,
+                            end: self
+                                .consume_and_return(Punctuation::CloseBracket)?
+                                .location
+                                .end,
+                        },
+                        left: Box::new(left),
+                        index: Box::new(index),
+                    }
+                }
                 Some(Token {
                     raw: RawToken::Punctuation(Punctuation::Dot),
                     ..
@@ -129,28 +463,41 @@ impl<'s> Parser<'s> {
                     }
                 }
                 Some(Token {
-                    raw: RawToken::Punctuation(Punctuation::OpenParent),
+                    raw: RawToken::Punctuation(Punctuation::QuestionDot),
                     ..
                 }) => {
-                    let mut arguments = vec![];
+                    if self.try_consume(Punctuation::OpenBracket) {
+                        let index = self.parse_expression(Precedence::Lowest)?;
 
-                    while self
-                        .lexer
-                        .peek()
-                        .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseParent))
-                    {
-                        arguments.push(self.parse_expression(Precedence::Lowest)?);
-
-                        if self
-                            .lexer
-                            .peek()
-                            .is_some_and(|token| token.raw == RawToken::from(Punctuation::Comma))
-                        {
-                            self.lexer.next();
-                        } else {
-                            break;
+                        Expression::OptionalIndex {
+                            location: Location {
+                                start: left.location().start,
+                                end: self
+                                    .consume_and_return(Punctuation::CloseBracket)?
+                                    .location
+                                    .end,
+                            },
+                            left: Box::new(left),
+                            index: Box::new(index),
+                        }
+                    } else {
+                        let right = self.consume_identifier()?;
+
+                        Expression::OptionalFieldAccess {
+                            location: Location {
+                                start: left.location().start,
+                                end: right.location.end,
+                            },
+                            left: Box::new(left),
+                            right,
                         }
                     }
+                }
+                Some(Token {
+                    raw: RawToken::Punctuation(Punctuation::OpenParent),
+                    ..
+                }) => {
+                    let arguments = self.parse_arguments()?;
 
                     Expression::Call {
                         location: Location {
@@ -171,29 +518,201 @@ impl<'s> Parser<'s> {
         Ok(left)
     }
 
+    /// Everything except assignment: `a + b = c` stops before `=`. Postfix
+    /// `++`/`--` share assignment's precedence, so `a++` stops before `++`
+    /// too.
+    pub fn parse_expression_no_assignment(&mut self) -> ParseResult<Expression> {
+        self.parse_expression(Precedence::Assign)
+    }
+
+    /// An operand of `+`/`-`: `a * b + c` stops before `+`.
+    pub fn parse_product(&mut self) -> ParseResult<Expression> {
+        self.parse_expression(Precedence::Sum)
+    }
+
+    /// A primary expression followed by calls, indexing, field accesses and
+    /// force unwraps: `a.b(c)[0] * d` stops before `*`.
+    pub fn parse_access_chain(&mut self) -> ParseResult<Expression> {
+        self.parse_expression(Precedence::Power)
+    }
+
+    /// Parses the arguments of a call up to, but not including, the closing
+    /// `)`. Positional arguments can't follow named ones.
+    fn parse_arguments(&mut self) -> ParseResult<Vec<Argument>> {
+        let mut arguments = vec![];
+
+        while self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseParent))
+        {
+            let start_token = self.lexer.peek().cloned();
+            let argument = self.parse_argument()?;
+
+            if argument.name.is_none()
+                && arguments
+                    .last()
+                    .is_some_and(|previous: &Argument| previous.name.is_some())
+            {
+                return Err(ParseError {
+                    kind: ParseErrorKind::UnexpectedToken,
+                    expected: "named argument".to_owned(),
+                    got: start_token,
+                });
+            }
+
+            arguments.push(argument);
+
+            if self
+                .lexer
+                .peek()
+                .is_some_and(|token| token.raw == RawToken::from(Punctuation::Comma))
+            {
+                self.lexer.next();
+            } else {
+                break;
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    fn parse_argument(&mut self) -> ParseResult<Argument> {
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        match value {
+            Expression::Identifier(name)
+                if self
+                    .lexer
+                    .peek()
+                    .is_some_and(|token| token.raw == RawToken::from(Punctuation::Colon)) =>
+            {
+                self.lexer.next();
+
+                Ok(Argument {
+                    name: Some(name),
+                    value: self.parse_expression(Precedence::Lowest)?,
+                })
+            }
+            value => Ok(Argument { name: None, value }),
+        }
+    }
+
+    /// Parses an expression and, with [`ParserOptions::comma_expressions`],
+    /// any `, expression` after it, binding looser than assignment.
+    fn parse_comma_expression(&mut self) -> ParseResult<Expression> {
+        let first = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.options.comma_expressions {
+            return Ok(first);
+        }
+
+        let mut expressions = vec![first];
+
+        while self.try_consume(Punctuation::Comma) {
+            expressions.push(self.parse_expression(Precedence::Lowest)?);
+        }
+
+        if expressions.len() == 1 {
+            return Ok(expressions.remove(0));
+        }
+
+        Ok(Expression::Comma {
+            location: Location {
+                start: expressions[0].location().start,
+                end: expressions[expressions.len() - 1].location().end,
+            },
+            expressions,
+        })
+    }
+
     fn parse_primary_expression(&mut self) -> ParseResult<Expression> {
         match self.lexer.next() {
             Some(Token {
                 raw: RawToken::Punctuation(Punctuation::OpenParent),
-                ..
+                location: Location { start, .. },
             }) => {
-                let inner = self.parse_expression(Precedence::Lowest)?;
-                self.consume(Punctuation::CloseParent)?;
+                let inner = self.parse_comma_expression()?;
+                let end = self
+                    .consume_and_return(Punctuation::CloseParent)?
+                    .location
+                    .end;
+
+                if self.options.preserve_parens {
+                    Ok(Expression::Grouping {
+                        inner: Box::new(inner),
+                        location: Location { start, end },
+                    })
+                } else {
+                    Ok(inner)
+                }
+            }
+            Some(
+                operator @ Token {
+                    raw:
+                        RawToken::Punctuation(Punctuation::Minus | Punctuation::Bang)
+                        | RawToken::Keyword(Keyword::Typeof | Keyword::Sizeof),
+                    ..
+                },
+            ) => {
+                let right = self.parse_expression(Precedence::Prefix)?;
 
-                Ok(inner)
+                Ok(Expression::Prefix {
+                    location: Location {
+                        start: operator.location.start,
+                        end: right.location().end,
+                    },
+                    operator,
+                    right: Box::new(right),
+                })
             }
             Some(Token {
                 raw: RawToken::Identifier(identifier),
                 location,
-            }) => Ok(Expression::Identifier(IdentifierAST {
-                identifier,
+            }) => {
+                let first = IdentifierAST {
+                    identifier,
+                    location,
+                };
+
+                if self
+                    .lexer
+                    .peek()
+                    .is_none_or(|token| token.raw != RawToken::from(Punctuation::ColonColon))
+                {
+                    return Ok(Expression::Identifier(first));
+                }
+
+                let mut segments = vec![first];
+
+                while self.try_consume(Punctuation::ColonColon) {
+                    segments.push(self.consume_identifier()?);
+                }
+
+                Ok(Expression::Path {
+                    location: Location {
+                        start: location.start,
+                        end: segments
+                            .last()
+                            .map_or(location.end, |last| last.location.end),
+                    },
+                    segments,
+                })
+            }
+            Some(Token {
+                raw: RawToken::IntegerLiteral { value, source_text },
+                location,
+            }) => Ok(Expression::Literal(Literal {
+                raw: RawLiteral::Integer(value),
+                source_text: (source_text != value.to_string()).then_some(source_text),
                 location,
             })),
             Some(Token {
-                raw: RawToken::IntegerLiteral(value),
+                raw: RawToken::FloatLiteral { value, source_text },
                 location,
             }) => Ok(Expression::Literal(Literal {
-                raw: RawLiteral::Integer(value),
+                raw: RawLiteral::Float(value),
+                source_text: (source_text != format!("{value:?}")).then_some(source_text),
                 location,
             })),
             Some(Token {
@@ -201,6 +720,7 @@ impl<'s> Parser<'s> {
                 location,
             }) => Ok(Expression::Literal(Literal {
                 raw: RawLiteral::Bool(value),
+                source_text: None,
                 location,
             })),
             Some(Token {
@@ -208,6 +728,15 @@ impl<'s> Parser<'s> {
                 location,
             }) => Ok(Expression::Literal(Literal {
                 raw: RawLiteral::String(value),
+                source_text: None,
+                location,
+            })),
+            Some(Token {
+                raw: RawToken::ByteStringLiteral(value),
+                location,
+            }) => Ok(Expression::Literal(Literal {
+                raw: RawLiteral::Bytes(value),
+                source_text: None,
                 location,
             })),
             Some(Token {
@@ -215,12 +744,41 @@ impl<'s> Parser<'s> {
                 location,
             }) => Ok(Expression::Literal(Literal {
                 raw: RawLiteral::Char(value),
+                source_text: None,
                 location,
             })),
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::New),
+                location: Location { start, .. },
+            }) => {
+                let mut class = vec![self.consume_identifier()?];
+
+                while self.try_consume(Punctuation::ColonColon) {
+                    class.push(self.consume_identifier()?);
+                }
+
+                self.consume(Punctuation::OpenParent)?;
+
+                let arguments = self.parse_arguments()?;
+
+                Ok(Expression::New {
+                    location: Location {
+                        start,
+                        end: self
+                            .consume_and_return(Punctuation::CloseParent)?
+                            .location
+                            .end,
+                    },
+                    class,
+                    arguments,
+                })
+            }
             Some(Token {
                 raw: RawToken::Keyword(Keyword::Fun),
                 location: Location { start, .. },
             }) => {
+                let type_parameters = self.parse_type_parameters()?;
+
                 self.consume(Punctuation::OpenParent)?;
 
                 let mut parameters = vec![];
@@ -245,44 +803,376 @@ impl<'s> Parser<'s> {
 
                 self.consume(Punctuation::CloseParent)?;
 
-                let block = self.parse_statements_block()?;
+                let block = self.parse_block(true)?;
 
                 Ok(Expression::Function {
                     location: Location {
                         start,
                         end: block.location.end,
                     },
+                    type_parameters,
                     parameters,
                     block,
                 })
             }
-            got => {
-                return Err(ParseError {
-                    expected: "expression".to_owned(),
-                    got,
-                })
-            }
+            got => Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: "expression".to_owned(),
+                got,
+            }),
         }
     }
 
-    pub fn parse_statement(&mut self) -> ParseResult<Statement> {
-        match self.lexer.peek() {
-            Some(Token {
-                raw: RawToken::Keyword(Keyword::Continue),
-                location,
-            }) => {
-                let start = location.start;
-                self.lexer.next();
+    fn parse_type_parameters(&mut self) -> ParseResult<Vec<TypeParameter>> {
+        if !self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw == RawToken::from(Punctuation::OpenBracket))
+        {
+            return Ok(vec![]);
+        }
 
-                Ok(Statement::Continue {
-                    location: Location {
-                        start,
-                        end: self
-                            .consume_and_return(Punctuation::Semicolon)?
-                            .location
-                            .end,
-                    },
-                })
+        self.lexer.next();
+
+        let mut type_parameters = vec![];
+
+        while self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseBracket))
+        {
+            let name = self.consume_identifier()?;
+
+            let bound = if self.try_consume(Punctuation::Colon) {
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+
+            type_parameters.push(TypeParameter {
+                location: Location {
+                    start: name.location.start,
+                    end: bound
+                        .as_ref()
+                        .map_or(name.location.end, |bound| bound.location().end),
+                },
+                name,
+                bound,
+            });
+
+            if self
+                .lexer
+                .peek()
+                .is_some_and(|token| token.raw == RawToken::from(Punctuation::Comma))
+            {
+                self.lexer.next();
+            } else {
+                break;
+            }
+        }
+
+        let close_bracket = self.consume_and_return(Punctuation::CloseBracket)?;
+
+        if type_parameters.is_empty() {
+            return Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: "type parameter".to_owned(),
+                got: Some(close_bracket),
+            });
+        }
+
+        Ok(type_parameters)
+    }
+
+    pub fn parse_pattern(&mut self) -> ParseResult<Pattern> {
+        let Some(open) = self.lexer.next_if(|token| {
+            token.raw == RawToken::from(Punctuation::OpenParent)
+                || token.raw == RawToken::from(Punctuation::OpenBracket)
+        }) else {
+            return Ok(Pattern::Identifier(self.consume_identifier()?));
+        };
+
+        let close = if open.raw == RawToken::from(Punctuation::OpenParent) {
+            Punctuation::CloseParent
+        } else {
+            Punctuation::CloseBracket
+        };
+
+        let mut elements = vec![];
+        let mut has_rest = false;
+
+        while self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw != RawToken::from(close))
+        {
+            if let Some(dots) = self
+                .lexer
+                .next_if(|token| token.raw == RawToken::from(Punctuation::DotDot))
+            {
+                if has_rest {
+                    return Err(ParseError {
+                        kind: ParseErrorKind::UnexpectedToken,
+                        expected: "pattern (only one `..` rest element is allowed)".to_owned(),
+                        got: Some(dots),
+                    });
+                }
+
+                has_rest = true;
+
+                let name = self.consume_identifier()?;
+
+                elements.push(Pattern::Rest {
+                    location: Location {
+                        start: dots.location.start,
+                        end: name.location.end,
+                    },
+                    name,
+                });
+            } else {
+                elements.push(self.parse_pattern()?);
+            }
+
+            if self
+                .lexer
+                .peek()
+                .is_some_and(|token| token.raw == RawToken::from(Punctuation::Comma))
+            {
+                self.lexer.next();
+            } else {
+                break;
+            }
+        }
+
+        let location = Location {
+            start: open.location.start,
+            end: self.consume_and_return(close)?.location.end,
+        };
+
+        Ok(if close == Punctuation::CloseParent {
+            Pattern::Tuple { elements, location }
+        } else {
+            Pattern::Array { elements, location }
+        })
+    }
+
+    pub fn parse_type(&mut self) -> ParseResult<TypeAST> {
+        let Some(Token {
+            location: Location { start, .. },
+            ..
+        }) = self
+            .lexer
+            .next_if(|token| token.raw == RawToken::from(Punctuation::OpenParent))
+        else {
+            return Ok(TypeAST::Name(self.consume_identifier()?));
+        };
+
+        let mut elements = vec![];
+
+        while self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseParent))
+        {
+            elements.push(self.parse_type()?);
+
+            if self
+                .lexer
+                .peek()
+                .is_some_and(|token| token.raw == RawToken::from(Punctuation::Comma))
+            {
+                self.lexer.next();
+            } else {
+                break;
+            }
+        }
+
+        Ok(TypeAST::Tuple {
+            location: Location {
+                start,
+                end: self
+                    .consume_and_return(Punctuation::CloseParent)?
+                    .location
+                    .end,
+            },
+            elements,
+        })
+    }
+
+    fn parse_doc_comments(&mut self) -> Option<String> {
+        let mut lines = vec![];
+
+        while let Some(Token {
+            raw: RawToken::DocComment(line),
+            ..
+        }) = self
+            .lexer
+            .next_if(|token| matches!(token.raw, RawToken::DocComment(..)))
+        {
+            lines.push(line);
+        }
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+
+    fn parse_enum_variant(&mut self) -> ParseResult<EnumVariant> {
+        // Variants don't keep their docs yet.
+        self.parse_doc_comments();
+
+        let name = self.consume_identifier()?;
+
+        let Some(_) = self
+            .lexer
+            .next_if(|token| token.raw == RawToken::from(Punctuation::OpenParent))
+        else {
+            return Ok(EnumVariant {
+                location: name.location,
+                name,
+                fields: vec![],
+            });
+        };
+
+        let mut fields = vec![];
+
+        while self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseParent))
+        {
+            fields.push(self.consume_identifier()?);
+
+            if self
+                .lexer
+                .peek()
+                .is_some_and(|token| token.raw == RawToken::from(Punctuation::Comma))
+            {
+                self.lexer.next();
+            } else {
+                break;
+            }
+        }
+
+        Ok(EnumVariant {
+            location: Location {
+                start: name.location.start,
+                end: self
+                    .consume_and_return(Punctuation::CloseParent)?
+                    .location
+                    .end,
+            },
+            name,
+            fields,
+        })
+    }
+
+    /// Parses `fun name(a, b);`. Trait methods only declare a signature, so
+    /// a body is an error.
+    fn parse_method_signature(&mut self) -> ParseResult<MethodSignature> {
+        // Neither do trait methods.
+        self.parse_doc_comments();
+
+        let start = self.consume_and_return(Keyword::Fun)?.location.start;
+        let name = self.consume_identifier()?;
+
+        self.consume(Punctuation::OpenParent)?;
+
+        let mut parameters = vec![];
+
+        while self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseParent))
+        {
+            parameters.push(self.consume_identifier()?);
+
+            if self
+                .lexer
+                .peek()
+                .is_some_and(|token| token.raw == RawToken::from(Punctuation::Comma))
+            {
+                self.lexer.next();
+            } else {
+                break;
+            }
+        }
+
+        self.consume(Punctuation::CloseParent)?;
+
+        match self.lexer.next() {
+            Some(Token {
+                raw: RawToken::Punctuation(Punctuation::Semicolon),
+                location,
+            }) => Ok(MethodSignature {
+                name,
+                parameters,
+                location: Location {
+                    start,
+                    end: location.end,
+                },
+            }),
+            got => Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: "`;` (trait methods have no body)".to_owned(),
+                got,
+            }),
+        }
+    }
+
+    /// Parses an optional `pub`/`private` modifier, which must be followed by
+    /// a declaration. Returns the modifier's start offset alongside it.
+    fn parse_visibility(&mut self) -> ParseResult<(Visibility, Option<usize>)> {
+        let (visibility, start) = match self.lexer.peek() {
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Pub),
+                location,
+            }) => (Visibility::Public, location.start),
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Private),
+                location,
+            }) => (Visibility::Private, location.start),
+            _ => return Ok((Visibility::Default, None)),
+        };
+
+        self.lexer.next();
+
+        match self.lexer.peek() {
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Var | Keyword::Enum | Keyword::Trait),
+                ..
+            }) => Ok((visibility, Some(start))),
+            got => Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: "declaration".to_owned(),
+                got: got.cloned(),
+            }),
+        }
+    }
+
+    pub fn parse_statement(&mut self) -> ParseResult<Statement> {
+        let doc = self.parse_doc_comments();
+        let (visibility, visibility_start) = self.parse_visibility()?;
+
+        match self.lexer.peek() {
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Continue),
+                location,
+            }) => {
+                let start = location.start;
+                self.lexer.next();
+
+                Ok(Statement::Continue {
+                    location: Location {
+                        start,
+                        end: self
+                            .consume_and_return(Punctuation::Semicolon)?
+                            .location
+                            .end,
+                    },
+                })
             }
             Some(Token {
                 raw: RawToken::Keyword(Keyword::Break),
@@ -291,6 +1181,8 @@ impl<'s> Parser<'s> {
                 let start = location.start;
                 self.lexer.next();
 
+                let value = self.parse_optional_value()?;
+
                 Ok(Statement::Break {
                     location: Location {
                         start,
@@ -299,6 +1191,7 @@ impl<'s> Parser<'s> {
                             .location
                             .end,
                     },
+                    value,
                 })
             }
             Some(Token {
@@ -307,7 +1200,7 @@ impl<'s> Parser<'s> {
             }) => {
                 let start = location.start;
                 self.lexer.next();
-                let return_value = self.parse_expression(Precedence::Lowest)?;
+                let return_value = self.parse_optional_value()?;
 
                 Ok(Statement::Return {
                     location: Location {
@@ -321,19 +1214,14 @@ impl<'s> Parser<'s> {
                 })
             }
             Some(Token {
-                raw: RawToken::Keyword(Keyword::Var),
+                raw: RawToken::Keyword(Keyword::Yield),
                 location,
             }) => {
                 let start = location.start;
                 self.lexer.next();
-
-                let name = self.consume_identifier()?;
-
-                self.consume(Punctuation::Eq)?;
-
                 let value = self.parse_expression(Precedence::Lowest)?;
 
-                Ok(Statement::Var {
+                Ok(Statement::Yield {
                     location: Location {
                         start,
                         end: self
@@ -341,72 +1229,2313 @@ impl<'s> Parser<'s> {
                             .location
                             .end,
                     },
-                    name,
                     value,
                 })
             }
-            _ => {
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Defer),
+                location,
+            }) => {
+                let start = location.start;
+                self.lexer.next();
                 let expression = self.parse_expression(Precedence::Lowest)?;
 
-                let result = Ok(Statement::Expression {
+                Ok(Statement::Defer {
                     location: Location {
-                        start: expression.location().start,
+                        start,
                         end: self
                             .consume_and_return(Punctuation::Semicolon)?
                             .location
                             .end,
                     },
                     expression,
-                });
+                })
+            }
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Assert),
+                location,
+            }) => {
+                let start = location.start;
+                self.lexer.next();
+                let condition = self.parse_expression(Precedence::Lowest)?;
 
-                result
+                let message = if self.try_consume(Punctuation::Comma) {
+                    Some(self.parse_expression(Precedence::Lowest)?)
+                } else {
+                    None
+                };
+
+                Ok(Statement::Assert {
+                    location: Location {
+                        start,
+                        end: self
+                            .consume_and_return(Punctuation::Semicolon)?
+                            .location
+                            .end,
+                    },
+                    condition,
+                    message,
+                })
             }
-        }
-    }
+            Some(Token {
+                raw: RawToken::Keyword(keyword @ (Keyword::Var | Keyword::Global | Keyword::Local)),
+                location,
+            }) => {
+                let kind = match keyword {
+                    Keyword::Global => VarKind::Global,
+                    Keyword::Local => VarKind::Local,
+                    _ => VarKind::Var,
+                };
+                let start = visibility_start.unwrap_or(location.start);
+                self.lexer.next();
 
-    pub fn parse_statements_block(&mut self) -> ParseResult<StatementsBlock> {
-        let start = self
-            .consume_and_return(Punctuation::OpenBrace)?
-            .location
-            .start;
+                let pattern = self.parse_pattern()?;
 
-        let mut statements = vec![];
+                let ty = if self.try_consume(Punctuation::Colon) {
+                    Some(self.parse_type()?)
+                } else {
+                    None
+                };
 
-        while self
-            .lexer
-            .peek()
-            .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseBrace))
-        {
-            statements.push(self.parse_statement()?);
-        }
+                if !self.try_consume(Punctuation::Eq) {
+                    return Err(ParseError {
+                        kind: ParseErrorKind::MissingInitializer,
+                        expected: "`=`".to_owned(),
+                        got: self.lexer.next(),
+                    });
+                }
 
-        Ok(StatementsBlock {
-            location: Location {
-                start,
-                end: self
-                    .consume_and_return(Punctuation::CloseBrace)?
-                    .location
-                    .end,
-            },
-            statements,
-        })
-    }
+                let value = self.parse_expression(Precedence::Lowest)?;
 
-    pub fn parse(&mut self) -> ParseResult<Module> {
-        let mut statements = vec![];
+                Ok(Statement::Var {
+                    location: Location {
+                        start,
+                        end: self
+                            .consume_and_return(Punctuation::Semicolon)?
+                            .location
+                            .end,
+                    },
+                    doc,
+                    visibility,
+                    kind,
+                    pattern,
+                    ty,
+                    value,
+                })
+            }
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Do),
+                location,
+            }) => {
+                let start = location.start;
+                self.lexer.next();
 
-        while self.lexer.peek().is_some() {
-            statements.push(self.parse_statement()?);
-        }
+                let body = self.parse_statements_block()?;
 
-        Ok(statements)
-    }
-}
+                self.consume(Keyword::While)?;
 
-#[derive(Debug, Clone, PartialEq)]
+                let condition = self.parse_expression(Precedence::Lowest)?;
+
+                Ok(Statement::DoWhile {
+                    location: Location {
+                        start,
+                        end: self
+                            .consume_and_return(Punctuation::Semicolon)?
+                            .location
+                            .end,
+                    },
+                    body,
+                    condition,
+                })
+            }
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Enum),
+                location,
+            }) => {
+                let start = visibility_start.unwrap_or(location.start);
+                self.lexer.next();
+
+                let name = self.consume_identifier()?;
+
+                self.consume(Punctuation::OpenBrace)?;
+
+                let mut variants = vec![];
+
+                while self
+                    .lexer
+                    .peek()
+                    .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseBrace))
+                {
+                    variants.push(self.parse_enum_variant()?);
+
+                    if self
+                        .lexer
+                        .peek()
+                        .is_some_and(|token| token.raw == RawToken::from(Punctuation::Comma))
+                    {
+                        self.lexer.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                Ok(Statement::Enum {
+                    location: Location {
+                        start,
+                        end: self
+                            .consume_and_return(Punctuation::CloseBrace)?
+                            .location
+                            .end,
+                    },
+                    doc,
+                    visibility,
+                    name,
+                    variants,
+                })
+            }
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Trait),
+                location,
+            }) => {
+                let start = visibility_start.unwrap_or(location.start);
+                self.lexer.next();
+
+                let name = self.consume_identifier()?;
+
+                self.consume(Punctuation::OpenBrace)?;
+
+                let mut methods = vec![];
+
+                while self
+                    .lexer
+                    .peek()
+                    .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseBrace))
+                {
+                    methods.push(self.parse_method_signature()?);
+                }
+
+                Ok(Statement::Trait {
+                    location: Location {
+                        start,
+                        end: self
+                            .consume_and_return(Punctuation::CloseBrace)?
+                            .location
+                            .end,
+                    },
+                    doc,
+                    visibility,
+                    name,
+                    methods,
+                })
+            }
+            Some(Token {
+                raw: RawToken::Keyword(Keyword::Switch),
+                location,
+            }) => {
+                let start = location.start;
+                self.lexer.next();
+
+                let scrutinee = self.parse_expression(Precedence::Lowest)?;
+
+                self.consume(Punctuation::OpenBrace)?;
+
+                let mut cases = vec![];
+
+                while let Some(Token {
+                    location: Location { start, .. },
+                    ..
+                }) = self
+                    .lexer
+                    .next_if(|token| token.raw == RawToken::from(Keyword::Case))
+                {
+                    let value = self.parse_expression(Precedence::Lowest)?;
+                    let block = self.parse_statements_block()?;
+
+                    cases.push(SwitchCase {
+                        location: Location {
+                            start,
+                            end: block.location.end,
+                        },
+                        value,
+                        block,
+                    });
+                }
+
+                let default = if self.match_keyword(Keyword::Default) {
+                    Some(self.parse_statements_block()?)
+                } else {
+                    None
+                };
+
+                Ok(Statement::Switch {
+                    location: Location {
+                        start,
+                        end: self
+                            .consume_and_return(Punctuation::CloseBrace)?
+                            .location
+                            .end,
+                    },
+                    scrutinee,
+                    cases,
+                    default,
+                })
+            }
+            Some(Token {
+                raw: RawToken::Punctuation(Punctuation::Semicolon),
+                location,
+            }) => {
+                let location = *location;
+                self.lexer.next();
+
+                Ok(Statement::Empty { location })
+            }
+            _ => {
+                let expression = self.parse_comma_expression()?;
+                self.finish_expression_statement(expression)
+            }
+        }
+    }
+
+    /// Parses the optional value of `return`/`break`, which is absent when the
+    /// statement ends right away.
+    fn parse_optional_value(&mut self) -> ParseResult<Option<Expression>> {
+        if self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw == RawToken::from(Punctuation::Semicolon))
+        {
+            Ok(None)
+        } else {
+            self.parse_expression(Precedence::Lowest).map(Some)
+        }
+    }
+
+    pub fn parse_statements_block(&mut self) -> ParseResult<StatementsBlock> {
+        self.parse_block(false)
+    }
+
+    /// Parses `{ ... }`. With `allow_tail`, the last expression statement
+    /// may leave out its `;` and becomes the block's [`StatementsBlock::tail`].
+    fn parse_block(&mut self, allow_tail: bool) -> ParseResult<StatementsBlock> {
+        let start = self
+            .consume_and_return(Punctuation::OpenBrace)?
+            .location
+            .start;
+
+        let mut statements = vec![];
+        let mut tail = None;
+
+        while self
+            .lexer
+            .peek()
+            .is_some_and(|token| token.raw != RawToken::from(Punctuation::CloseBrace))
+        {
+            if !(allow_tail && self.at_expression_statement()) {
+                statements.push(self.parse_statement()?);
+                continue;
+            }
+
+            let expression = self.parse_comma_expression()?;
+
+            if self
+                .lexer
+                .peek()
+                .is_some_and(|token| token.raw == RawToken::from(Punctuation::CloseBrace))
+            {
+                tail = Some(Box::new(expression));
+            } else {
+                statements.push(self.finish_expression_statement(expression)?);
+            }
+        }
+
+        Ok(StatementsBlock {
+            location: Location {
+                start,
+                end: self
+                    .consume_and_return(Punctuation::CloseBrace)?
+                    .location
+                    .end,
+            },
+            statements,
+            tail,
+        })
+    }
+
+    /// Whether the next statement is a plain expression followed by `;`,
+    /// rather than one introduced by a keyword, modifier or doc comment.
+    fn at_expression_statement(&mut self) -> bool {
+        match self.lexer.peek().map(|token| &token.raw) {
+            Some(RawToken::Keyword(keyword)) => !keyword.is_statement_only(),
+            Some(RawToken::DocComment(..) | RawToken::Punctuation(Punctuation::Semicolon)) => false,
+            _ => true,
+        }
+    }
+
+    fn finish_expression_statement(&mut self, expression: Expression) -> ParseResult<Statement> {
+        Ok(Statement::Expression {
+            location: Location {
+                start: expression.location().start,
+                end: self
+                    .consume_and_return(Punctuation::Semicolon)?
+                    .location
+                    .end,
+            },
+            expression,
+        })
+    }
+
+    /// Consumes the leading `//!` lines of a file, joined with newlines.
+    pub fn parse_module_doc(&mut self) -> Option<String> {
+        let mut lines = vec![];
+
+        while let Some(Token {
+            raw: RawToken::ModuleDocComment(line),
+            ..
+        }) = self
+            .lexer
+            .next_if(|token| matches!(token.raw, RawToken::ModuleDocComment(..)))
+        {
+            lines.push(line);
+        }
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+
+    pub fn is_at_end(&mut self) -> bool {
+        self.lexer.peek().is_none()
+    }
+
+    /// How many tokens the parser has consumed so far. Comments, and doc
+    /// comments that don't document a statement, never reach it.
+    pub fn tokens_consumed(&self) -> usize {
+        self.lexer.consumed
+    }
+
+    pub fn parse(&mut self) -> ParseResult<Module> {
+        let doc = self.parse_module_doc();
+
+        let mut statements = vec![];
+
+        while !self.is_at_end() {
+            statements.push(self.parse_statement()?);
+        }
+
+        Ok(Module { doc, statements })
+    }
+
+    /// Parses statements up to the first one that fails, and returns them
+    /// along with every token from the start of that statement onwards. An
+    /// empty list of leftovers means the whole input was consumed.
+    pub fn parse_all(&mut self) -> (Module, Vec<Token>) {
+        let doc = self.parse_module_doc();
+
+        let mut statements = vec![];
+
+        while !self.is_at_end() {
+            let checkpoint = self.lexer.clone();
+
+            match self.parse_statement() {
+                Ok(statement) => statements.push(statement),
+                Err(..) => {
+                    self.lexer = checkpoint;
+                    break;
+                }
+            }
+        }
+
+        (Module { doc, statements }, self.lexer.by_ref().collect())
+    }
+}
+
+impl FromStr for Module {
+    type Err = ParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Parser::new(source).parse()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A token that doesn't fit the grammar at this point.
+    UnexpectedToken,
+    /// A `var` declaration without `= value`.
+    MissingInitializer,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParseError {
+    pub kind: ParseErrorKind,
     pub expected: String,
     pub got: Option<Token>,
 }
 
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.got {
+            Some(Token {
+                raw: RawToken::Keyword(keyword),
+                ..
+            }) if self.expected == "expression" && keyword.is_statement_only() => write!(
+                f,
+                "{} is a statement and cannot be used as an expression here",
+                keyword
+            ),
+            Some(Token {
+                raw: RawToken::Punctuation(Punctuation::Semicolon),
+                ..
+            })
+            | None
+                if self.kind == ParseErrorKind::MissingInitializer =>
+            {
+                f.write_str("variables must be initialized, add an initializer with `= value`")
+            }
+            Some(got) if self.kind == ParseErrorKind::MissingInitializer => {
+                write!(f, "expected `=` before the initializer, found {}", got.raw)
+            }
+            Some(got) => write!(f, "expected {}, found {}", self.expected, got.raw),
+            None => write!(f, "expected {}, found end of input", self.expected),
+        }
+    }
+}
+
 pub type ParseResult<T> = Result<T, ParseError>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{
+            Argument, EnumVariant, Expression, IdentifierAST, Literal, MethodSignature, Module,
+            Pattern, RawLiteral, Statement, StatementsBlock, SwitchCase, TypeAST, TypeParameter,
+            VarKind, Visibility,
+        },
+        token::{Keyword, Location, Precedence, Punctuation, RawToken, Token},
+    };
+
+    use crate::ast_eq::statement_eq_ignoring_locations;
+
+    use super::{ParseError, ParseErrorKind, Parser, ParserOptions, PrecedenceTable};
+
+    #[test]
+    fn break_without_value() {
+        let mut parser = Parser::new("break;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Break {
+                location: Location { start: 0, end: 6 },
+                value: None,
+            })
+        );
+    }
+
+    #[test]
+    fn break_with_value() {
+        let mut parser = Parser::new("break a;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Break {
+                location: Location { start: 0, end: 8 },
+                value: Some(Expression::Identifier(IdentifierAST {
+                    identifier: "a".to_owned(),
+                    location: Location { start: 6, end: 7 },
+                })),
+            })
+        );
+    }
+
+    #[test]
+    fn generic_function() {
+        let mut parser = Parser::new("fun [T, U] (xs, f) {}");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Function {
+                type_parameters: vec![
+                    TypeParameter {
+                        name: identifier("T", 5),
+                        bound: None,
+                        location: Location { start: 5, end: 6 },
+                    },
+                    TypeParameter {
+                        name: identifier("U", 8),
+                        bound: None,
+                        location: Location { start: 8, end: 9 },
+                    },
+                ],
+                parameters: vec![
+                    IdentifierAST {
+                        identifier: "xs".to_owned(),
+                        location: Location { start: 12, end: 14 },
+                    },
+                    IdentifierAST {
+                        identifier: "f".to_owned(),
+                        location: Location { start: 16, end: 17 },
+                    },
+                ],
+                block: StatementsBlock {
+                    statements: vec![],
+                    tail: None,
+                    location: Location { start: 19, end: 21 },
+                },
+                location: Location { start: 0, end: 21 },
+            })
+        );
+    }
+
+    #[test]
+    fn bounded_type_parameter() {
+        let mut parser = Parser::new("fun [T: Comparable, U] (a, b) {}");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Function {
+                type_parameters: vec![
+                    TypeParameter {
+                        name: identifier("T", 5),
+                        bound: Some(TypeAST::Name(identifier("Comparable", 8))),
+                        location: Location { start: 5, end: 18 },
+                    },
+                    TypeParameter {
+                        name: identifier("U", 20),
+                        bound: None,
+                        location: Location { start: 20, end: 21 },
+                    },
+                ],
+                parameters: vec![identifier("a", 24), identifier("b", 27)],
+                block: StatementsBlock {
+                    statements: vec![],
+                    tail: None,
+                    location: Location { start: 30, end: 32 },
+                },
+                location: Location { start: 0, end: 32 },
+            })
+        );
+    }
+
+    #[test]
+    fn function_body_tail_expression() {
+        let mut parser = Parser::new("fun (a, b) { a + b }");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Function {
+                type_parameters: vec![],
+                parameters: vec![identifier("a", 5), identifier("b", 8)],
+                block: StatementsBlock {
+                    statements: vec![],
+                    tail: Some(Box::new(Expression::Binary {
+                        left: Box::new(Expression::Identifier(identifier("a", 13))),
+                        right: Box::new(Expression::Identifier(identifier("b", 17))),
+                        operator: Token {
+                            raw: RawToken::Punctuation(Punctuation::Plus),
+                            location: Location { start: 15, end: 16 },
+                        },
+                        location: Location { start: 13, end: 18 },
+                    })),
+                    location: Location { start: 11, end: 20 },
+                },
+                location: Location { start: 0, end: 20 },
+            })
+        );
+    }
+
+    #[test]
+    fn tail_expression_follows_statements() {
+        let mut parser = Parser::new("fun () { var x = 1; f(x); x }");
+
+        let Ok(Expression::Function { block, .. }) = parser.parse_expression(Precedence::Lowest)
+        else {
+            panic!("expected a function");
+        };
+
+        assert_eq!(block.statements.len(), 2);
+        assert_eq!(
+            block.tail,
+            Some(Box::new(Expression::Identifier(identifier("x", 26))))
+        );
+    }
+
+    #[test]
+    fn semicolon_leaves_no_tail() {
+        let mut parser = Parser::new("fun () { x; }");
+
+        let Ok(Expression::Function { block, .. }) = parser.parse_expression(Precedence::Lowest)
+        else {
+            panic!("expected a function");
+        };
+
+        assert_eq!(block.statements.len(), 1);
+        assert_eq!(block.tail, None);
+    }
+
+    #[test]
+    fn tail_expression_only_in_function_bodies() {
+        let mut parser = Parser::new("do { x } while y;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: "`;`".to_owned(),
+                got: Some(Token {
+                    raw: RawToken::Punctuation(Punctuation::CloseBrace),
+                    location: Location { start: 7, end: 8 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn empty_type_parameters() {
+        let mut parser = Parser::new("fun [] () {}");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: "type parameter".to_owned(),
+                got: Some(Token {
+                    raw: RawToken::Punctuation(Punctuation::CloseBracket),
+                    location: Location { start: 5, end: 6 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn do_while() {
+        let mut parser = Parser::new("do { break; continue; } while a;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::DoWhile {
+                location: Location { start: 0, end: 32 },
+                body: StatementsBlock {
+                    statements: vec![
+                        Statement::Break {
+                            location: Location { start: 5, end: 11 },
+                            value: None,
+                        },
+                        Statement::Continue {
+                            location: Location { start: 12, end: 21 },
+                        },
+                    ],
+                    tail: None,
+                    location: Location { start: 3, end: 23 },
+                },
+                condition: Expression::Identifier(IdentifierAST {
+                    identifier: "a".to_owned(),
+                    location: Location { start: 30, end: 31 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn doc_comment_attaches_to_declaration() {
+        let mut parser = Parser::new(
+            "/// Adds one.
+/// Works on integers.
+var inc = fun (n) { n + 1; };",
+        );
+
+        let Ok(Statement::Var { doc, .. }) = parser.parse_statement() else {
+            panic!("expected var statement");
+        };
+
+        assert_eq!(doc.as_deref(), Some("Adds one.\nWorks on integers."));
+    }
+
+    #[test]
+    fn doc_comments_inside_statements_are_ignored() {
+        for (source, without_docs) in [
+            ("var x = 1 + /// note\n 2;", "var x = 1 + 2;"),
+            ("f(a, /// the b\n b);", "f(a, b);"),
+            ("a;\n/// trailing", "a;"),
+            (
+                "var f = fun () { a; /// done\n };",
+                "var f = fun () { a; };",
+            ),
+            (
+                "do { /// body\n a; } /// cond\n while b;",
+                "do { a; } while b;",
+            ),
+            (
+                "switch a { /// one\n case 1 {} /// other\n default {} }",
+                "switch a { case 1 {} default {} }",
+            ),
+            (
+                "enum E { /// first\n A, /// second\n B }",
+                "enum E { A, B }",
+            ),
+            ("trait T { /// m\n fun m(); }", "trait T { fun m(); }"),
+        ] {
+            let module = Parser::new(source).parse().unwrap();
+            let expected = Parser::new(without_docs).parse().unwrap();
+
+            assert_eq!(
+                module.statements.len(),
+                expected.statements.len(),
+                "{source}"
+            );
+            assert!(
+                module
+                    .statements
+                    .iter()
+                    .zip(&expected.statements)
+                    .all(|(a, b)| statement_eq_ignoring_locations(a, b)),
+                "{source}"
+            );
+        }
+    }
+
+    fn literal(value: u64, start: usize) -> Box<Expression> {
+        Box::new(Expression::Literal(Literal {
+            raw: RawLiteral::Integer(value),
+            source_text: None,
+            location: Location {
+                start,
+                end: start + 1,
+            },
+        }))
+    }
+
+    #[test]
+    fn literals_format_as_written() {
+        let Ok(Expression::Literal(literal)) =
+            Parser::new("007").parse_expression(Precedence::Lowest)
+        else {
+            panic!("expected a literal");
+        };
+
+        assert_eq!(literal.raw, RawLiteral::Integer(7));
+        assert_eq!(literal.to_string(), "007");
+
+        for (source, source_text) in [
+            ("0xFF_00", Some("0xFF_00")),
+            ("1_000", Some("1_000")),
+            ("1e10", Some("1e10")),
+            ("1.0", None),
+        ] {
+            let Ok(Expression::Literal(literal)) =
+                Parser::new(source).parse_expression(Precedence::Lowest)
+            else {
+                panic!("expected a literal");
+            };
+
+            assert_eq!(literal.source_text.as_deref(), source_text);
+            assert_eq!(literal.to_string(), source);
+        }
+        assert_eq!(
+            Literal {
+                raw: RawLiteral::String("a\"b\n".to_owned()),
+                source_text: None,
+                location: Location::EMPTY,
+            }
+            .to_string(),
+            "\"a\\\"b\\n\""
+        );
+    }
+
+    #[test]
+    fn caret_is_xor_by_default() {
+        let mut parser = Parser::new("2 ^ 3 + 1");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Binary {
+                left: literal(2, 0),
+                right: Box::new(Expression::Binary {
+                    left: literal(3, 4),
+                    right: literal(1, 8),
+                    operator: Token {
+                        raw: RawToken::Punctuation(Punctuation::Plus),
+                        location: Location { start: 6, end: 7 },
+                    },
+                    location: Location { start: 4, end: 9 },
+                }),
+                operator: Token {
+                    raw: RawToken::Punctuation(Punctuation::Caret),
+                    location: Location { start: 2, end: 3 },
+                },
+                location: Location { start: 0, end: 9 },
+            })
+        );
+    }
+
+    #[test]
+    fn caret_is_power() {
+        let mut parser = Parser::new("2 ^ 3 + 1").with_options(ParserOptions {
+            caret_is_power: true,
+            ..ParserOptions::default()
+        });
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Binary {
+                left: Box::new(Expression::Binary {
+                    left: literal(2, 0),
+                    right: literal(3, 4),
+                    operator: Token {
+                        raw: RawToken::Punctuation(Punctuation::StarStar),
+                        location: Location { start: 2, end: 3 },
+                    },
+                    location: Location { start: 0, end: 5 },
+                }),
+                right: literal(1, 8),
+                operator: Token {
+                    raw: RawToken::Punctuation(Punctuation::Plus),
+                    location: Location { start: 6, end: 7 },
+                },
+                location: Location { start: 0, end: 9 },
+            })
+        );
+    }
+
+    fn with_comma_expressions(source: &str) -> Parser<'_> {
+        Parser::new(source).with_options(ParserOptions {
+            comma_expressions: true,
+            ..ParserOptions::default()
+        })
+    }
+
+    #[test]
+    fn comma_expression_statement() {
+        let module = with_comma_expressions("a = 1, b = 2;").parse().unwrap();
+
+        let Statement::Expression {
+            expression:
+                Expression::Comma {
+                    expressions,
+                    location,
+                },
+            ..
+        } = &module.statements[0]
+        else {
+            panic!("expected a comma expression statement");
+        };
+
+        assert_eq!(*location, Location { start: 0, end: 12 });
+        assert!(expressions
+            .iter()
+            .all(|expression| matches!(expression, Expression::Assignment { .. })));
+        assert_eq!(expressions.len(), 2);
+    }
+
+    #[test]
+    fn parenthesized_comma_expression() {
+        assert_eq!(
+            with_comma_expressions("f((a, b), c)").parse_expression(Precedence::Lowest),
+            Ok(Expression::Call {
+                callee: Box::new(Expression::Identifier(identifier("f", 0))),
+                arguments: vec![
+                    Argument {
+                        name: None,
+                        value: Expression::Comma {
+                            expressions: vec![
+                                Expression::Identifier(identifier("a", 3)),
+                                Expression::Identifier(identifier("b", 6)),
+                            ],
+                            location: Location { start: 3, end: 7 },
+                        },
+                    },
+                    Argument {
+                        name: None,
+                        value: Expression::Identifier(identifier("c", 10)),
+                    },
+                ],
+                location: Location { start: 0, end: 12 },
+            })
+        );
+    }
+
+    #[test]
+    fn comma_expressions_are_opt_in() {
+        assert_eq!(expression_error("(a, b);"), "expected `)`, found `,`");
+        assert_eq!(expression_error("a, b;"), "expected `;`, found `,`");
+    }
+
+    fn power(start: usize) -> Token {
+        Token {
+            raw: RawToken::Punctuation(Punctuation::StarStar),
+            location: Location {
+                start,
+                end: start + 2,
+            },
+        }
+    }
+
+    fn minus(start: usize) -> Token {
+        Token {
+            raw: RawToken::Punctuation(Punctuation::Minus),
+            location: Location {
+                start,
+                end: start + 1,
+            },
+        }
+    }
+
+    #[test]
+    fn power_binds_tighter_than_minus() {
+        let mut parser = Parser::new("-2 ** 2");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Prefix {
+                operator: minus(0),
+                right: Box::new(Expression::Binary {
+                    left: literal(2, 1),
+                    right: literal(2, 6),
+                    operator: power(3),
+                    location: Location { start: 1, end: 7 },
+                }),
+                location: Location { start: 0, end: 7 },
+            })
+        );
+    }
+
+    #[test]
+    fn negative_exponent() {
+        let mut parser = Parser::new("2 ** -2");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Binary {
+                left: literal(2, 0),
+                right: Box::new(Expression::Prefix {
+                    operator: minus(5),
+                    right: literal(2, 6),
+                    location: Location { start: 5, end: 7 },
+                }),
+                operator: power(2),
+                location: Location { start: 0, end: 7 },
+            })
+        );
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        let mut parser = Parser::new("2 ** 3 ** 2");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Binary {
+                left: literal(2, 0),
+                right: Box::new(Expression::Binary {
+                    left: literal(3, 5),
+                    right: literal(2, 10),
+                    operator: power(7),
+                    location: Location { start: 5, end: 11 },
+                }),
+                operator: power(2),
+                location: Location { start: 0, end: 11 },
+            })
+        );
+    }
+
+    #[test]
+    fn new_with_arguments() {
+        let mut parser = Parser::new("new Point(1, 2)");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::New {
+                class: vec![identifier("Point", 4)],
+                arguments: vec![
+                    Argument {
+                        name: None,
+                        value: *literal(1, 10),
+                    },
+                    Argument {
+                        name: None,
+                        value: *literal(2, 13),
+                    },
+                ],
+                location: Location { start: 0, end: 15 },
+            })
+        );
+    }
+
+    #[test]
+    fn new_without_arguments() {
+        let mut parser = Parser::new("new shapes::Empty()");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::New {
+                class: vec![identifier("shapes", 4), identifier("Empty", 12)],
+                arguments: vec![],
+                location: Location { start: 0, end: 19 },
+            })
+        );
+    }
+
+    #[test]
+    fn precedence_table_override() {
+        let mut parser = Parser::new("a + b * c").with_precedence_table(
+            PrecedenceTable::default()
+                .with(Punctuation::Plus, Precedence::Product)
+                .with(Punctuation::Star, Precedence::Sum),
+        );
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Binary {
+                left: Box::new(Expression::Binary {
+                    left: Box::new(Expression::Identifier(identifier("a", 0))),
+                    right: Box::new(Expression::Identifier(identifier("b", 4))),
+                    operator: Token {
+                        raw: RawToken::Punctuation(Punctuation::Plus),
+                        location: Location { start: 2, end: 3 },
+                    },
+                    location: Location { start: 0, end: 5 },
+                }),
+                right: Box::new(Expression::Identifier(identifier("c", 8))),
+                operator: Token {
+                    raw: RawToken::Punctuation(Punctuation::Star),
+                    location: Location { start: 6, end: 7 },
+                },
+                location: Location { start: 0, end: 9 },
+            })
+        );
+    }
+
+    fn identifier(identifier: &str, start: usize) -> IdentifierAST {
+        IdentifierAST {
+            identifier: identifier.to_owned(),
+            location: Location {
+                start,
+                end: start + identifier.len(),
+            },
+        }
+    }
+
+    #[test]
+    fn positional_arguments() {
+        let mut parser = Parser::new("f(a, b)");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Call {
+                callee: Box::new(Expression::Identifier(identifier("f", 0))),
+                arguments: vec![
+                    Argument {
+                        name: None,
+                        value: Expression::Identifier(identifier("a", 2)),
+                    },
+                    Argument {
+                        name: None,
+                        value: Expression::Identifier(identifier("b", 5)),
+                    },
+                ],
+                location: Location { start: 0, end: 7 },
+            })
+        );
+    }
+
+    #[test]
+    fn named_arguments() {
+        let mut parser = Parser::new("f(x: 1, y: 2)");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Call {
+                callee: Box::new(Expression::Identifier(identifier("f", 0))),
+                arguments: vec![
+                    Argument {
+                        name: Some(identifier("x", 2)),
+                        value: *literal(1, 5),
+                    },
+                    Argument {
+                        name: Some(identifier("y", 8)),
+                        value: *literal(2, 11),
+                    },
+                ],
+                location: Location { start: 0, end: 13 },
+            })
+        );
+    }
+
+    #[test]
+    fn positional_argument_after_named() {
+        let mut parser = Parser::new("f(x: 1, 2)");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: "named argument".to_owned(),
+                got: Some(Token {
+                    raw: RawToken::IntegerLiteral {
+                        value: 2,
+                        source_text: "2".to_owned(),
+                    },
+                    location: Location { start: 8, end: 9 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn parentheses_are_dropped_by_default() {
+        let mut parser = Parser::new("(a)");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Identifier(identifier("a", 1)))
+        );
+    }
+
+    #[test]
+    fn preserve_parens() {
+        let mut parser = Parser::new("(1 + 2)").with_options(ParserOptions {
+            preserve_parens: true,
+            ..ParserOptions::default()
+        });
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Grouping {
+                inner: Box::new(Expression::Binary {
+                    left: literal(1, 1),
+                    right: literal(2, 5),
+                    operator: Token {
+                        raw: RawToken::Punctuation(Punctuation::Plus),
+                        location: Location { start: 3, end: 4 },
+                    },
+                    location: Location { start: 1, end: 6 },
+                }),
+                location: Location { start: 0, end: 7 },
+            })
+        );
+    }
+
+    #[test]
+    fn switch_with_default() {
+        let mut parser = Parser::new("switch x { case 1 { a; } case 2 {} default { b; } }");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Switch {
+                location: Location { start: 0, end: 51 },
+                scrutinee: Expression::Identifier(identifier("x", 7)),
+                cases: vec![
+                    SwitchCase {
+                        value: *literal(1, 16),
+                        block: StatementsBlock {
+                            statements: vec![Statement::Expression {
+                                location: Location { start: 20, end: 22 },
+                                expression: Expression::Identifier(identifier("a", 20)),
+                            }],
+                            tail: None,
+                            location: Location { start: 18, end: 24 },
+                        },
+                        location: Location { start: 11, end: 24 },
+                    },
+                    SwitchCase {
+                        value: *literal(2, 30),
+                        block: StatementsBlock {
+                            statements: vec![],
+                            tail: None,
+                            location: Location { start: 32, end: 34 },
+                        },
+                        location: Location { start: 25, end: 34 },
+                    },
+                ],
+                default: Some(StatementsBlock {
+                    statements: vec![Statement::Expression {
+                        location: Location { start: 45, end: 47 },
+                        expression: Expression::Identifier(identifier("b", 45)),
+                    }],
+                    tail: None,
+                    location: Location { start: 43, end: 49 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn switch_without_default() {
+        let mut parser = Parser::new("switch x { case 1 {} }");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Switch {
+                location: Location { start: 0, end: 22 },
+                scrutinee: Expression::Identifier(identifier("x", 7)),
+                cases: vec![SwitchCase {
+                    value: *literal(1, 16),
+                    block: StatementsBlock {
+                        statements: vec![],
+                        tail: None,
+                        location: Location { start: 18, end: 20 },
+                    },
+                    location: Location { start: 11, end: 20 },
+                }],
+                default: None,
+            })
+        );
+    }
+
+    #[test]
+    fn typed_var() {
+        let mut parser = Parser::new("var a: Int = 1;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Var {
+                location: Location { start: 0, end: 15 },
+                doc: None,
+                visibility: Visibility::Default,
+                kind: VarKind::Var,
+                pattern: Pattern::Identifier(identifier("a", 4)),
+                ty: Some(TypeAST::Name(identifier("Int", 7))),
+                value: *literal(1, 13),
+            })
+        );
+    }
+
+    #[test]
+    fn global_and_local_bindings() {
+        for (source, kind) in [
+            ("var x = 1;", VarKind::Var),
+            ("global x = 1;", VarKind::Global),
+            ("local x = 1;", VarKind::Local),
+        ] {
+            let keyword_len = source.find(' ').unwrap();
+            let mut parser = Parser::new(source);
+
+            assert_eq!(
+                parser.parse_statement(),
+                Ok(Statement::Var {
+                    location: Location {
+                        start: 0,
+                        end: source.len(),
+                    },
+                    doc: None,
+                    visibility: Visibility::Default,
+                    kind,
+                    pattern: Pattern::Identifier(identifier("x", keyword_len + 1)),
+                    ty: None,
+                    value: *literal(1, keyword_len + 5),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn typed_tuple_destructuring() {
+        let mut parser = Parser::new("var (a, b): (Int, Int) = pair;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Var {
+                location: Location { start: 0, end: 30 },
+                doc: None,
+                visibility: Visibility::Default,
+                kind: VarKind::Var,
+                pattern: Pattern::Tuple {
+                    elements: vec![
+                        Pattern::Identifier(identifier("a", 5)),
+                        Pattern::Identifier(identifier("b", 8)),
+                    ],
+                    location: Location { start: 4, end: 10 },
+                },
+                ty: Some(TypeAST::Tuple {
+                    elements: vec![
+                        TypeAST::Name(identifier("Int", 13)),
+                        TypeAST::Name(identifier("Int", 18)),
+                    ],
+                    location: Location { start: 12, end: 22 },
+                }),
+                value: Expression::Identifier(identifier("pair", 25)),
+            })
+        );
+    }
+
+    #[test]
+    fn trailing_rest_pattern() {
+        let mut parser = Parser::new("var [first, ..rest] = arr;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Var {
+                location: Location { start: 0, end: 26 },
+                doc: None,
+                visibility: Visibility::Default,
+                kind: VarKind::Var,
+                pattern: Pattern::Array {
+                    elements: vec![
+                        Pattern::Identifier(identifier("first", 5)),
+                        Pattern::Rest {
+                            name: identifier("rest", 14),
+                            location: Location { start: 12, end: 18 },
+                        },
+                    ],
+                    location: Location { start: 4, end: 19 },
+                },
+                ty: None,
+                value: Expression::Identifier(identifier("arr", 22)),
+            })
+        );
+    }
+
+    #[test]
+    fn leading_rest_pattern() {
+        let mut parser = Parser::new("[..init, last]");
+
+        assert_eq!(
+            parser.parse_pattern(),
+            Ok(Pattern::Array {
+                elements: vec![
+                    Pattern::Rest {
+                        name: identifier("init", 3),
+                        location: Location { start: 1, end: 7 },
+                    },
+                    Pattern::Identifier(identifier("last", 9)),
+                ],
+                location: Location { start: 0, end: 14 },
+            })
+        );
+    }
+
+    #[test]
+    fn misplaced_rest_pattern() {
+        assert_eq!(
+            expression_error("var [..a, ..b] = arr;"),
+            "expected pattern (only one `..` rest element is allowed), found `..`"
+        );
+        assert_eq!(
+            expression_error("var ..rest = arr;"),
+            "expected identifier, found `..`"
+        );
+    }
+
+    fn expression_error(source: &str) -> String {
+        Parser::new(source)
+            .parse_statement()
+            .expect_err("expected a parse error")
+            .to_string()
+    }
+
+    #[test]
+    fn statement_keyword_in_expression_position() {
+        assert_eq!(
+            expression_error("var x = while true {};"),
+            "`while` is a statement and cannot be used as an expression here"
+        );
+        assert_eq!(
+            expression_error("f(var);"),
+            "`var` is a statement and cannot be used as an expression here"
+        );
+        assert_eq!(
+            expression_error("var x = return 1;"),
+            "`return` is a statement and cannot be used as an expression here"
+        );
+        assert_eq!(
+            expression_error("var x = pub;"),
+            "`pub` is a statement and cannot be used as an expression here"
+        );
+        assert_eq!(
+            expression_error("f(private);"),
+            "`private` is a statement and cannot be used as an expression here"
+        );
+        assert_eq!(
+            expression_error("var x = case;"),
+            "`case` is a statement and cannot be used as an expression here"
+        );
+        assert_eq!(
+            expression_error("f(default);"),
+            "`default` is a statement and cannot be used as an expression here"
+        );
+    }
+
+    #[test]
+    fn var_without_initializer() {
+        assert_eq!(
+            expression_error("var x;"),
+            "variables must be initialized, add an initializer with `= value`"
+        );
+        assert_eq!(
+            expression_error("var x"),
+            "variables must be initialized, add an initializer with `= value`"
+        );
+        assert_eq!(
+            Parser::new("var x;").parse().unwrap_err().kind,
+            ParseErrorKind::MissingInitializer
+        );
+    }
+
+    #[test]
+    fn var_missing_eq() {
+        assert_eq!(
+            expression_error("var x 5;"),
+            "expected `=` before the initializer, found 5"
+        );
+        assert_eq!(
+            expression_error("var x: Int 5;"),
+            "expected `=` before the initializer, found 5"
+        );
+    }
+
+    #[test]
+    fn unexpected_token_message() {
+        assert_eq!(
+            expression_error("var x = ;"),
+            "expected expression, found `;`"
+        );
+        assert_eq!(
+            expression_error("var x ="),
+            "expected expression, found end of input"
+        );
+    }
+
+    #[test]
+    fn public_declaration() {
+        let mut parser = Parser::new("pub var f = fun () {};");
+
+        let Ok(Statement::Var {
+            location,
+            visibility,
+            ..
+        }) = parser.parse_statement()
+        else {
+            panic!("expected var statement");
+        };
+
+        assert_eq!(visibility, Visibility::Public);
+        assert_eq!(location, Location { start: 0, end: 22 });
+    }
+
+    #[test]
+    fn private_declaration() {
+        let mut parser = Parser::new("private var x = 1;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Var {
+                location: Location { start: 0, end: 18 },
+                doc: None,
+                visibility: Visibility::Private,
+                kind: VarKind::Var,
+                pattern: Pattern::Identifier(identifier("x", 12)),
+                ty: None,
+                value: *literal(1, 16),
+            })
+        );
+    }
+
+    #[test]
+    fn visibility_requires_declaration() {
+        let mut parser = Parser::new("pub 1;");
+
+        assert_eq!(
+            parser.parse_statement().map_err(|error| error.to_string()),
+            Err("expected declaration, found 1".to_owned())
+        );
+    }
+
+    #[test]
+    fn return_without_value() {
+        let mut parser = Parser::new("return;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Return {
+                location: Location { start: 0, end: 7 },
+                return_value: None,
+            })
+        );
+    }
+
+    #[test]
+    fn return_call() {
+        let mut parser = Parser::new("return f();");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Return {
+                location: Location { start: 0, end: 11 },
+                return_value: Some(Expression::Call {
+                    callee: Box::new(Expression::Identifier(identifier("f", 7))),
+                    arguments: vec![],
+                    location: Location { start: 7, end: 10 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn return_prefix() {
+        let mut parser = Parser::new("return -x;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Return {
+                location: Location { start: 0, end: 10 },
+                return_value: Some(Expression::Prefix {
+                    operator: Token {
+                        raw: RawToken::Punctuation(Punctuation::Minus),
+                        location: Location { start: 7, end: 8 },
+                    },
+                    right: Box::new(Expression::Identifier(identifier("x", 8))),
+                    location: Location { start: 7, end: 9 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn return_binary() {
+        let mut parser = Parser::new("return -a * b;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Return {
+                location: Location { start: 0, end: 14 },
+                return_value: Some(Expression::Binary {
+                    left: Box::new(Expression::Prefix {
+                        operator: Token {
+                            raw: RawToken::Punctuation(Punctuation::Minus),
+                            location: Location { start: 7, end: 8 },
+                        },
+                        right: Box::new(Expression::Identifier(identifier("a", 8))),
+                        location: Location { start: 7, end: 9 },
+                    }),
+                    right: Box::new(Expression::Identifier(identifier("b", 12))),
+                    operator: Token {
+                        raw: RawToken::Punctuation(Punctuation::Star),
+                        location: Location { start: 10, end: 11 },
+                    },
+                    location: Location { start: 7, end: 13 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn optional_field_access() {
+        let mut parser = Parser::new("a?.b");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::OptionalFieldAccess {
+                left: Box::new(Expression::Identifier(identifier("a", 0))),
+                right: identifier("b", 3),
+                location: Location { start: 0, end: 4 },
+            })
+        );
+    }
+
+    #[test]
+    fn optional_field_access_chain() {
+        let mut parser = Parser::new("a?.b.c");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::FieldAccess {
+                left: Box::new(Expression::OptionalFieldAccess {
+                    left: Box::new(Expression::Identifier(identifier("a", 0))),
+                    right: identifier("b", 3),
+                    location: Location { start: 0, end: 4 },
+                }),
+                right: identifier("c", 5),
+                location: Location { start: 0, end: 6 },
+            })
+        );
+    }
+
+    #[test]
+    fn optional_index() {
+        let mut parser = Parser::new("a?.[0]");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::OptionalIndex {
+                left: Box::new(Expression::Identifier(identifier("a", 0))),
+                index: literal(0, 4),
+                location: Location { start: 0, end: 6 },
+            })
+        );
+    }
+
+    #[test]
+    fn typeof_expression() {
+        let mut parser = Parser::new("typeof x");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Prefix {
+                operator: Token {
+                    raw: Keyword::Typeof.into(),
+                    location: Location { start: 0, end: 6 },
+                },
+                right: Box::new(Expression::Identifier(identifier("x", 7))),
+                location: Location { start: 0, end: 8 },
+            })
+        );
+    }
+
+    #[test]
+    fn sizeof_expression() {
+        let mut parser = Parser::new("sizeof arr.items");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Prefix {
+                operator: Token {
+                    raw: Keyword::Sizeof.into(),
+                    location: Location { start: 0, end: 6 },
+                },
+                right: Box::new(Expression::FieldAccess {
+                    left: Box::new(Expression::Identifier(identifier("arr", 7))),
+                    right: identifier("items", 11),
+                    location: Location { start: 7, end: 16 },
+                }),
+                location: Location { start: 0, end: 16 },
+            })
+        );
+    }
+
+    #[test]
+    fn path() {
+        let mut parser = Parser::new("a::b");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Path {
+                segments: vec![identifier("a", 0), identifier("b", 3)],
+                location: Location { start: 0, end: 4 },
+            })
+        );
+    }
+
+    #[test]
+    fn path_call() {
+        let mut parser = Parser::new("a::b::c(1)");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Call {
+                callee: Box::new(Expression::Path {
+                    segments: vec![identifier("a", 0), identifier("b", 3), identifier("c", 6)],
+                    location: Location { start: 0, end: 7 },
+                }),
+                arguments: vec![Argument {
+                    name: None,
+                    value: *literal(1, 8),
+                }],
+                location: Location { start: 0, end: 10 },
+            })
+        );
+    }
+
+    #[test]
+    fn long_addition_chain() {
+        let source = vec!["1"; 10_000].join(" + ");
+        let expression = Parser::new(&source)
+            .parse_expression(Precedence::Lowest)
+            .unwrap();
+
+        assert_eq!(
+            expression.location(),
+            Location {
+                start: 0,
+                end: source.len()
+            }
+        );
+    }
+
+    #[test]
+    fn long_mixed_precedence_chain() {
+        let source = vec!["a * b ^ c"; 5_000].join(" + ");
+        let expression = Parser::new(&source)
+            .parse_expression(Precedence::Lowest)
+            .unwrap();
+
+        assert_eq!(
+            expression.location(),
+            Location {
+                start: 0,
+                end: source.len()
+            }
+        );
+    }
+
+    #[test]
+    fn expression_without_assignment() {
+        let mut parser = Parser::new("a + b = c");
+
+        assert_eq!(
+            parser
+                .parse_expression_no_assignment()
+                .map(|expression| expression.location()),
+            Ok(Location { start: 0, end: 5 })
+        );
+        assert_eq!(
+            parser.lexer.next().map(|token| token.raw),
+            Some(Punctuation::Eq.into())
+        );
+
+        let mut parser = Parser::new("a++");
+
+        assert_eq!(
+            parser.parse_expression_no_assignment(),
+            Ok(Expression::Identifier(identifier("a", 0)))
+        );
+        assert_eq!(
+            parser.lexer.next().map(|token| token.raw),
+            Some(Punctuation::PlusPlus.into())
+        );
+    }
+
+    #[test]
+    fn product() {
+        let mut parser = Parser::new("a * b + c");
+
+        assert_eq!(
+            parser
+                .parse_product()
+                .map(|expression| expression.location()),
+            Ok(Location { start: 0, end: 5 })
+        );
+        assert_eq!(
+            parser.lexer.next().map(|token| token.raw),
+            Some(Punctuation::Plus.into())
+        );
+    }
+
+    #[test]
+    fn access_chain() {
+        let mut parser = Parser::new("a.b(c)[0] * d");
+
+        assert_eq!(
+            parser
+                .parse_access_chain()
+                .map(|expression| expression.location()),
+            Ok(Location { start: 0, end: 9 })
+        );
+        assert_eq!(
+            parser.lexer.next().map(|token| token.raw),
+            Some(Punctuation::Star.into())
+        );
+    }
+
+    #[test]
+    fn assert_statement() {
+        let mut parser = Parser::new("assert ok;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Assert {
+                location: Location { start: 0, end: 10 },
+                condition: Expression::Identifier(identifier("ok", 7)),
+                message: None,
+            })
+        );
+    }
+
+    #[test]
+    fn assert_statement_with_message() {
+        let mut parser = Parser::new("assert ok, \"must be positive\";");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Assert {
+                location: Location { start: 0, end: 30 },
+                condition: Expression::Identifier(identifier("ok", 7)),
+                message: Some(Expression::Literal(Literal {
+                    raw: RawLiteral::String("must be positive".to_owned()),
+                    source_text: None,
+                    location: Location { start: 11, end: 29 },
+                })),
+            })
+        );
+    }
+
+    #[test]
+    fn yield_statement() {
+        let mut parser = Parser::new("yield x;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Yield {
+                location: Location { start: 0, end: 8 },
+                value: Expression::Identifier(identifier("x", 6)),
+            })
+        );
+    }
+
+    #[test]
+    fn defer_statement() {
+        let mut parser = Parser::new("defer close(file);");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Defer {
+                location: Location { start: 0, end: 18 },
+                expression: Expression::Call {
+                    callee: Box::new(Expression::Identifier(identifier("close", 6))),
+                    arguments: vec![Argument {
+                        name: None,
+                        value: Expression::Identifier(identifier("file", 12)),
+                    }],
+                    location: Location { start: 6, end: 17 },
+                },
+            })
+        );
+    }
+
+    fn bang(start: usize) -> Token {
+        Token {
+            raw: RawToken::Punctuation(Punctuation::Bang),
+            location: Location {
+                start,
+                end: start + 1,
+            },
+        }
+    }
+
+    #[test]
+    fn force_unwrap() {
+        let mut parser = Parser::new("a!");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::ForceUnwrap {
+                expression: Box::new(Expression::Identifier(identifier("a", 0))),
+                location: Location { start: 0, end: 2 },
+            })
+        );
+    }
+
+    #[test]
+    fn force_unwrap_then_field_access() {
+        let mut parser = Parser::new("a!.b");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::FieldAccess {
+                left: Box::new(Expression::ForceUnwrap {
+                    expression: Box::new(Expression::Identifier(identifier("a", 0))),
+                    location: Location { start: 0, end: 2 },
+                }),
+                right: identifier("b", 3),
+                location: Location { start: 0, end: 4 },
+            })
+        );
+    }
+
+    #[test]
+    fn detached_bang_is_not_force_unwrap() {
+        for source in ["a ! b", "a !b", "a!b"] {
+            let mut parser = Parser::new(source);
+
+            assert_eq!(
+                parser.parse_expression(Precedence::Lowest),
+                Ok(Expression::Identifier(identifier("a", 0)))
+            );
+            assert_eq!(
+                parser.lexer.next().map(|token| token.raw),
+                Some(Punctuation::Bang.into())
+            );
+        }
+    }
+
+    #[test]
+    fn force_unwrap_then_call() {
+        let mut parser = Parser::new("f!()");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Call {
+                callee: Box::new(Expression::ForceUnwrap {
+                    expression: Box::new(Expression::Identifier(identifier("f", 0))),
+                    location: Location { start: 0, end: 2 },
+                }),
+                arguments: vec![],
+                location: Location { start: 0, end: 4 },
+            })
+        );
+    }
+
+    #[test]
+    fn prefix_not() {
+        let mut parser = Parser::new("!a");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Prefix {
+                operator: bang(0),
+                right: Box::new(Expression::Identifier(identifier("a", 1))),
+                location: Location { start: 0, end: 2 },
+            })
+        );
+    }
+
+    #[test]
+    fn prefix_not_of_force_unwrap() {
+        let mut parser = Parser::new("!a!");
+
+        assert_eq!(
+            parser.parse_expression(Precedence::Lowest),
+            Ok(Expression::Prefix {
+                operator: bang(0),
+                right: Box::new(Expression::ForceUnwrap {
+                    expression: Box::new(Expression::Identifier(identifier("a", 1))),
+                    location: Location { start: 1, end: 3 },
+                }),
+                location: Location { start: 0, end: 3 },
+            })
+        );
+    }
+
+    #[test]
+    fn expect_any() {
+        let terminators = [
+            RawToken::from(Punctuation::Semicolon),
+            RawToken::from(Punctuation::CloseBrace),
+        ];
+        let mut parser = Parser::new("; } )");
+
+        assert_eq!(
+            parser.expect_any(&terminators),
+            Ok(Token {
+                raw: RawToken::Punctuation(Punctuation::Semicolon),
+                location: Location { start: 0, end: 1 },
+            })
+        );
+        assert_eq!(
+            parser.expect_any(&terminators),
+            Ok(Token {
+                raw: RawToken::Punctuation(Punctuation::CloseBrace),
+                location: Location { start: 2, end: 3 },
+            })
+        );
+        assert_eq!(
+            parser
+                .expect_any(&terminators)
+                .map_err(|error| error.to_string()),
+            Err("expected `;` or `}`, found `)`".to_owned())
+        );
+    }
+
+    #[test]
+    fn try_consume() {
+        let mut parser = Parser::new("; default a");
+
+        assert!(!parser.try_consume(Punctuation::Comma));
+        assert!(parser.try_consume(Punctuation::Semicolon));
+        assert!(!parser.match_keyword(Keyword::Case));
+        assert!(parser.match_keyword(Keyword::Default));
+        assert_eq!(parser.consume_identifier(), Ok(identifier("a", 10)));
+        assert!(!parser.try_consume(Punctuation::Semicolon));
+    }
+
+    #[test]
+    fn integer_in_error_keeps_source_text() {
+        let error = ParseError {
+            kind: ParseErrorKind::UnexpectedToken,
+            expected: "`;`".to_owned(),
+            got: Some(Token {
+                raw: RawToken::IntegerLiteral {
+                    value: 255,
+                    source_text: "0xFF".to_owned(),
+                },
+                location: Location { start: 0, end: 4 },
+            }),
+        };
+
+        assert_eq!(error.to_string(), "expected `;`, found 0xFF");
+    }
+
+    #[test]
+    fn assignment_to_member_chain() {
+        let mut parser = Parser::new("a.b[0].c = 1;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Expression {
+                location: Location { start: 0, end: 13 },
+                expression: Expression::Assignment {
+                    target: Box::new(Expression::FieldAccess {
+                        left: Box::new(Expression::Index {
+                            left: Box::new(Expression::FieldAccess {
+                                left: Box::new(Expression::Identifier(identifier("a", 0))),
+                                right: identifier("b", 2),
+                                location: Location { start: 0, end: 3 },
+                            }),
+                            index: literal(0, 4),
+                            location: Location { start: 0, end: 6 },
+                        }),
+                        right: identifier("c", 7),
+                        location: Location { start: 0, end: 8 },
+                    }),
+                    operator: Token {
+                        raw: RawToken::Punctuation(Punctuation::Eq),
+                        location: Location { start: 9, end: 10 },
+                    },
+                    value: literal(1, 11),
+                    location: Location { start: 0, end: 12 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn logical_compound_assignments() {
+        for (source, operator) in [
+            ("a ??= b", Punctuation::QuestionQuestionEq),
+            ("a &&= b", Punctuation::AmpersandAmpersandEq),
+            ("a ||= b", Punctuation::PipePipeEq),
+        ] {
+            assert_eq!(
+                Parser::new(source).parse_expression(Precedence::Lowest),
+                Ok(Expression::Assignment {
+                    target: Box::new(Expression::Identifier(identifier("a", 0))),
+                    operator: Token {
+                        raw: operator.into(),
+                        location: Location { start: 2, end: 5 },
+                    },
+                    value: Box::new(Expression::Identifier(identifier("b", 6))),
+                    location: Location { start: 0, end: 7 },
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn assignment_is_right_associative() {
+        let mut parser = Parser::new("a = b += 1");
+
+        let Ok(Expression::Assignment { target, value, .. }) =
+            parser.parse_expression(Precedence::Lowest)
+        else {
+            panic!("expected assignment");
+        };
+
+        assert_eq!(*target, Expression::Identifier(identifier("a", 0)));
+        assert!(matches!(*value, Expression::Assignment { .. }));
+    }
+
+    #[test]
+    fn assignment_to_call_result() {
+        let mut parser = Parser::new("a.b()[0] = 1;");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                expected: "assignable expression".to_owned(),
+                got: Some(Token {
+                    raw: RawToken::Punctuation(Punctuation::Eq),
+                    location: Location { start: 9, end: 10 },
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn simple_enum() {
+        let mut parser = Parser::new("enum Color { Red, Green, Blue, }");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Enum {
+                location: Location { start: 0, end: 32 },
+                doc: None,
+                visibility: Visibility::Default,
+                name: identifier("Color", 5),
+                variants: vec![
+                    EnumVariant {
+                        name: identifier("Red", 13),
+                        fields: vec![],
+                        location: Location { start: 13, end: 16 },
+                    },
+                    EnumVariant {
+                        name: identifier("Green", 18),
+                        fields: vec![],
+                        location: Location { start: 18, end: 23 },
+                    },
+                    EnumVariant {
+                        name: identifier("Blue", 25),
+                        fields: vec![],
+                        location: Location { start: 25, end: 29 },
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn enum_with_payloads() {
+        let mut parser = Parser::new("pub enum Shape { Circle(r), Rect(w, h) }");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Enum {
+                location: Location { start: 0, end: 40 },
+                doc: None,
+                visibility: Visibility::Public,
+                name: identifier("Shape", 9),
+                variants: vec![
+                    EnumVariant {
+                        name: identifier("Circle", 17),
+                        fields: vec![identifier("r", 24)],
+                        location: Location { start: 17, end: 26 },
+                    },
+                    EnumVariant {
+                        name: identifier("Rect", 28),
+                        fields: vec![identifier("w", 33), identifier("h", 36)],
+                        location: Location { start: 28, end: 38 },
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn trait_declaration() {
+        let mut parser = Parser::new("trait Drawable { fun draw(); fun move_by(dx, dy); }");
+
+        assert_eq!(
+            parser.parse_statement(),
+            Ok(Statement::Trait {
+                location: Location { start: 0, end: 51 },
+                doc: None,
+                visibility: Visibility::Default,
+                name: identifier("Drawable", 6),
+                methods: vec![
+                    MethodSignature {
+                        name: identifier("draw", 21),
+                        parameters: vec![],
+                        location: Location { start: 17, end: 28 },
+                    },
+                    MethodSignature {
+                        name: identifier("move_by", 33),
+                        parameters: vec![identifier("dx", 41), identifier("dy", 45)],
+                        location: Location { start: 29, end: 49 },
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn trait_method_with_body() {
+        assert_eq!(
+            expression_error("trait Drawable { fun draw() {} }"),
+            "expected `;` (trait methods have no body), found `{`"
+        );
+    }
+
+    #[test]
+    fn parse_all_complete() {
+        let (module, leftovers) = Parser::new("a; b;").parse_all();
+
+        assert_eq!(module.statements.len(), 2);
+        assert_eq!(leftovers, vec![]);
+    }
+
+    #[test]
+    fn parse_all_leftovers() {
+        let (module, leftovers) = Parser::new("a; f(b").parse_all();
+
+        assert_eq!(module.statements.len(), 1);
+        assert_eq!(
+            leftovers,
+            vec![
+                Token {
+                    raw: RawToken::Identifier("f".to_owned()),
+                    location: Location { start: 3, end: 4 },
+                },
+                Token {
+                    raw: RawToken::Punctuation(Punctuation::OpenParent),
+                    location: Location { start: 4, end: 5 },
+                },
+                Token {
+                    raw: RawToken::Identifier("b".to_owned()),
+                    location: Location { start: 5, end: 6 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_statement() {
+        let mut parser = Parser::new(";");
+
+        assert_eq!(
+            parser.parse(),
+            Ok(Module {
+                doc: None,
+                statements: vec![Statement::Empty {
+                    location: Location { start: 0, end: 1 },
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn multiple_empty_statements() {
+        let mut parser = Parser::new(";;;");
+
+        assert_eq!(
+            parser.parse(),
+            Ok(Module {
+                doc: None,
+                statements: vec![
+                    Statement::Empty {
+                        location: Location { start: 0, end: 1 },
+                    },
+                    Statement::Empty {
+                        location: Location { start: 1, end: 2 },
+                    },
+                    Statement::Empty {
+                        location: Location { start: 2, end: 3 },
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn module_from_str() {
+        assert_eq!(
+            "//! Docs.\n;".parse::<Module>(),
+            Ok(Module {
+                doc: Some("Docs.".to_owned()),
+                statements: vec![Statement::Empty {
+                    location: Location { start: 10, end: 11 },
+                }],
+            })
+        );
+        assert_eq!(
+            "var = 1;"
+                .parse::<Module>()
+                .map_err(|error| error.to_string()),
+            Err("expected identifier, found `=`".to_owned())
+        );
+    }
+
+    #[test]
+    fn module_doc() {
+        let mut parser = Parser::new("//! Math helpers.\n//!\n//! More.\n;\n//! Not docs.\n");
+
+        assert_eq!(
+            parser.parse(),
+            Ok(Module {
+                doc: Some("Math helpers.\n\nMore.".to_owned()),
+                statements: vec![Statement::Empty {
+                    location: Location { start: 32, end: 33 },
+                }],
+            })
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use crate::ast::{
+    Argument, EnumVariant, Expression, IdentifierAST, MethodSignature, Module, Pattern, Statement,
+    StatementsBlock, TypeAST, TypeParameter,
+};
+use alloc::string::String;
+
+/// Applies `rename` to every identifier in the module: variable uses and
+/// bindings, parameters, field names, argument labels, path segments, type
+/// names and enum declarations. Identifiers for which `rename` returns `None`
+/// are left alone. Locations are never touched.
+///
+/// This is purely syntactic: shadowing is not taken into account, so every
+/// identifier with a matching name is renamed, whatever it refers to.
+pub fn rename_identifiers(module: &mut Module, rename: impl Fn(&str) -> Option<String>) {
+    for statement in &mut module.statements {
+        rename_in_statement(statement, &rename);
+    }
+}
+
+fn rename_identifier(identifier: &mut IdentifierAST, rename: &impl Fn(&str) -> Option<String>) {
+    if let Some(new_name) = rename(&identifier.identifier) {
+        identifier.identifier = new_name;
+    }
+}
+
+fn rename_in_block(block: &mut StatementsBlock, rename: &impl Fn(&str) -> Option<String>) {
+    for statement in &mut block.statements {
+        rename_in_statement(statement, rename);
+    }
+
+    if let Some(tail) = &mut block.tail {
+        rename_in_expression(tail, rename);
+    }
+}
+
+fn rename_in_statement(statement: &mut Statement, rename: &impl Fn(&str) -> Option<String>) {
+    match statement {
+        Statement::Expression { expression, .. } | Statement::Defer { expression, .. } => {
+            rename_in_expression(expression, rename);
+        }
+        Statement::Return {
+            return_value: value,
+            ..
+        }
+        | Statement::Break { value, .. } => {
+            if let Some(value) = value {
+                rename_in_expression(value, rename);
+            }
+        }
+        Statement::Continue { .. } | Statement::Empty { .. } => {}
+        Statement::Var {
+            pattern, ty, value, ..
+        } => {
+            rename_in_pattern(pattern, rename);
+
+            if let Some(ty) = ty {
+                rename_in_type(ty, rename);
+            }
+
+            rename_in_expression(value, rename);
+        }
+        Statement::Yield { value, .. } => rename_in_expression(value, rename),
+        Statement::Assert {
+            condition, message, ..
+        } => {
+            rename_in_expression(condition, rename);
+
+            if let Some(message) = message {
+                rename_in_expression(message, rename);
+            }
+        }
+        Statement::DoWhile {
+            body, condition, ..
+        } => {
+            rename_in_block(body, rename);
+            rename_in_expression(condition, rename);
+        }
+        Statement::Switch {
+            scrutinee,
+            cases,
+            default,
+            ..
+        } => {
+            rename_in_expression(scrutinee, rename);
+
+            for case in cases {
+                rename_in_expression(&mut case.value, rename);
+                rename_in_block(&mut case.block, rename);
+            }
+
+            if let Some(default) = default {
+                rename_in_block(default, rename);
+            }
+        }
+        Statement::Enum { name, variants, .. } => {
+            rename_identifier(name, rename);
+
+            for EnumVariant { name, fields, .. } in variants {
+                rename_identifier(name, rename);
+
+                for field in fields {
+                    rename_identifier(field, rename);
+                }
+            }
+        }
+        Statement::Trait { name, methods, .. } => {
+            rename_identifier(name, rename);
+
+            for MethodSignature {
+                name, parameters, ..
+            } in methods
+            {
+                rename_identifier(name, rename);
+
+                for parameter in parameters {
+                    rename_identifier(parameter, rename);
+                }
+            }
+        }
+    }
+}
+
+fn rename_in_expression(expression: &mut Expression, rename: &impl Fn(&str) -> Option<String>) {
+    match expression {
+        Expression::Literal(..) => {}
+        Expression::Identifier(identifier) => rename_identifier(identifier, rename),
+        Expression::Path { segments, .. } => {
+            for segment in segments {
+                rename_identifier(segment, rename);
+            }
+        }
+        Expression::Binary { left, right, .. } => {
+            rename_in_expression(left, rename);
+            rename_in_expression(right, rename);
+        }
+        Expression::Postfix { left, .. } => rename_in_expression(left, rename),
+        Expression::FieldAccess { left, right, .. }
+        | Expression::OptionalFieldAccess { left, right, .. } => {
+            rename_in_expression(left, rename);
+            rename_identifier(right, rename);
+        }
+        Expression::Prefix { right, .. } => rename_in_expression(right, rename),
+        Expression::Index { left, index, .. } | Expression::OptionalIndex { left, index, .. } => {
+            rename_in_expression(left, rename);
+            rename_in_expression(index, rename);
+        }
+        Expression::Assignment { target, value, .. } => {
+            rename_in_expression(target, rename);
+            rename_in_expression(value, rename);
+        }
+        Expression::Call {
+            callee, arguments, ..
+        } => {
+            rename_in_expression(callee, rename);
+            rename_in_arguments(arguments, rename);
+        }
+        Expression::New {
+            class, arguments, ..
+        } => {
+            for segment in class {
+                rename_identifier(segment, rename);
+            }
+
+            rename_in_arguments(arguments, rename);
+        }
+        Expression::Grouping { inner, .. }
+        | Expression::ForceUnwrap {
+            expression: inner, ..
+        } => rename_in_expression(inner, rename),
+        Expression::Function {
+            type_parameters,
+            parameters,
+            block,
+            ..
+        } => {
+            for TypeParameter { name, bound, .. } in type_parameters {
+                rename_identifier(name, rename);
+
+                if let Some(bound) = bound {
+                    rename_in_type(bound, rename);
+                }
+            }
+
+            for parameter in parameters {
+                rename_identifier(parameter, rename);
+            }
+
+            rename_in_block(block, rename);
+        }
+        Expression::Comma { expressions, .. } => {
+            for expression in expressions {
+                rename_in_expression(expression, rename);
+            }
+        }
+    }
+}
+
+fn rename_in_arguments(arguments: &mut [Argument], rename: &impl Fn(&str) -> Option<String>) {
+    for argument in arguments {
+        if let Some(name) = &mut argument.name {
+            rename_identifier(name, rename);
+        }
+
+        rename_in_expression(&mut argument.value, rename);
+    }
+}
+
+fn rename_in_pattern(pattern: &mut Pattern, rename: &impl Fn(&str) -> Option<String>) {
+    match pattern {
+        Pattern::Identifier(identifier)
+        | Pattern::Rest {
+            name: identifier, ..
+        } => rename_identifier(identifier, rename),
+        Pattern::Tuple { elements, .. } | Pattern::Array { elements, .. } => {
+            for element in elements {
+                rename_in_pattern(element, rename);
+            }
+        }
+    }
+}
+
+fn rename_in_type(ty: &mut TypeAST, rename: &impl Fn(&str) -> Option<String>) {
+    match ty {
+        TypeAST::Name(name) => rename_identifier(name, rename),
+        TypeAST::Tuple { elements, .. } => {
+            for element in elements {
+                rename_in_type(element, rename);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{Expression, Pattern, Statement},
+        parser::Parser,
+        token::Location,
+    };
+
+    use super::rename_identifiers;
+
+    #[test]
+    fn renames_every_occurrence() {
+        let source = "var x = 1; var f = fun (x) { x.x(x: x + 1); }; f(x)!;";
+        let mut module = Parser::new(source).parse().unwrap();
+        let expected = Parser::new(&source.replace('x', "y")).parse().unwrap();
+
+        rename_identifiers(&mut module, |name| (name == "x").then(|| "y".to_owned()));
+
+        // `x` and `y` have the same length, so even locations line up.
+        assert_eq!(module, expected);
+    }
+
+    #[test]
+    fn keeps_locations_and_other_names() {
+        let mut module = Parser::new("var abc = b;").parse().unwrap();
+
+        rename_identifiers(&mut module, |name| (name == "abc").then(|| "a".to_owned()));
+
+        let Statement::Var {
+            pattern: Pattern::Identifier(name),
+            value: Expression::Identifier(value),
+            ..
+        } = &module.statements[0]
+        else {
+            panic!("expected a var statement");
+        };
+
+        assert_eq!(name.identifier, "a");
+        assert_eq!(name.location, Location { start: 4, end: 7 });
+        assert_eq!(value.identifier, "b");
+    }
+}
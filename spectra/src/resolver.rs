@@ -0,0 +1,177 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    ast::{Expression, IdentifierAST, Module, Pattern, Statement, StatementsBlock},
+    token::Location,
+    visit::{self, Visitor},
+};
+
+/// A name introduced by a `var` statement or a function parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub identifier: IdentifierAST,
+    pub kind: BindingKind,
+    /// Whether the name is mentioned anywhere in its scope after being
+    /// declared.
+    pub used: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    /// Bound by the `var` statement at `statement`.
+    Var {
+        statement: Location,
+    },
+    Parameter,
+}
+
+/// Resolves every identifier in `module` to the innermost binding of that
+/// name in scope, and returns all bindings in source order. Blocks and
+/// function bodies each open a new scope.
+#[must_use]
+pub fn resolve(module: &Module) -> Vec<Binding> {
+    let mut resolver = Resolver {
+        scopes: vec![vec![]],
+        bindings: vec![],
+    };
+
+    for statement in &module.statements {
+        resolver.visit_statement(statement);
+    }
+
+    resolver.pop_scope();
+    resolver
+        .bindings
+        .sort_by_key(|binding| binding.identifier.location.start);
+    resolver.bindings
+}
+
+struct Resolver {
+    scopes: Vec<Vec<Binding>>,
+    /// Bindings whose scope has been closed.
+    bindings: Vec<Binding>,
+}
+
+impl Resolver {
+    fn declare(&mut self, identifier: &IdentifierAST, kind: BindingKind) {
+        self.scopes
+            .last_mut()
+            .expect("there is always a module scope")
+            .push(Binding {
+                identifier: identifier.clone(),
+                kind,
+                used: false,
+            });
+    }
+
+    fn declare_pattern(&mut self, pattern: &Pattern, statement: Location) {
+        match pattern {
+            Pattern::Identifier(identifier)
+            | Pattern::Rest {
+                name: identifier, ..
+            } => self.declare(identifier, BindingKind::Var { statement }),
+            Pattern::Tuple { elements, .. } | Pattern::Array { elements, .. } => {
+                for element in elements {
+                    self.declare_pattern(element, statement);
+                }
+            }
+        }
+    }
+
+    fn pop_scope(&mut self) {
+        let scope = self.scopes.pop().expect("scopes are balanced");
+
+        self.bindings.extend(scope);
+    }
+}
+
+impl Visitor for Resolver {
+    fn visit_statements_block(&mut self, block: &StatementsBlock) {
+        self.scopes.push(vec![]);
+        visit::walk_statements_block(self, block);
+        self.pop_scope();
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        visit::walk_statement(self, statement);
+
+        if let Statement::Var {
+            pattern, location, ..
+        } = statement
+        {
+            self.declare_pattern(pattern, *location);
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Identifier(identifier) => {
+                if let Some(binding) = self
+                    .scopes
+                    .iter_mut()
+                    .rev()
+                    .flat_map(|scope| scope.iter_mut().rev())
+                    .find(|binding| binding.identifier.identifier == identifier.identifier)
+                {
+                    binding.used = true;
+                }
+            }
+            Expression::Function { parameters, .. } => {
+                self.scopes.push(vec![]);
+
+                for parameter in parameters {
+                    self.declare(parameter, BindingKind::Parameter);
+                }
+
+                visit::walk_expression(self, expression);
+                self.pop_scope();
+            }
+            _ => visit::walk_expression(self, expression),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parser::Parser, token::Location};
+
+    use super::{resolve, BindingKind};
+
+    #[test]
+    fn bindings_in_source_order() {
+        let module = Parser::new("var a = 1; var f = fun (x) { var b = a; };")
+            .parse()
+            .unwrap();
+        let bindings = resolve(&module);
+
+        assert_eq!(
+            bindings
+                .iter()
+                .map(|binding| (binding.identifier.identifier.as_str(), binding.used))
+                .collect::<Vec<_>>(),
+            vec![("a", true), ("f", false), ("x", false), ("b", false)]
+        );
+        assert_eq!(bindings[2].kind, BindingKind::Parameter);
+        assert_eq!(
+            bindings[3].kind,
+            BindingKind::Var {
+                statement: Location { start: 29, end: 39 }
+            }
+        );
+    }
+
+    #[test]
+    fn inner_binding_shadows_outer() {
+        let module = Parser::new("var a = 1; do { var a = 2; a; } while true;")
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            resolve(&module)
+                .iter()
+                .map(|binding| binding.used)
+                .collect::<Vec<_>>(),
+            vec![false, true]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::{collections::VecDeque, io, io::BufRead};
+
+use crate::{
+    lexer::Lexer,
+    token::{LexError, Location, RawToken, Token},
+};
+
+/// Lexes a [`BufRead`] line by line instead of reading the whole source up
+/// front. Token locations are byte offsets into the full stream, exactly as
+/// [`Lexer`] would report them for the same source.
+pub struct StreamingLexer<R> {
+    reader: R,
+    /// Source read from `reader` that hasn't been lexed yet.
+    buffer: String,
+    /// Stream offset of the first byte in `buffer`.
+    buffer_offset: usize,
+    tokens: VecDeque<Token>,
+    eof: bool,
+    /// Whether every token lexed so far was a `//!` line, so `buffer` still
+    /// starts inside the module header.
+    in_module_header: bool,
+}
+
+impl<R: BufRead> StreamingLexer<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            buffer_offset: 0,
+            tokens: VecDeque::new(),
+            eof: false,
+            in_module_header: true,
+        }
+    }
+
+    /// Reads one more line and, unless the buffered source ends inside a
+    /// token that can span lines, lexes everything buffered so far.
+    fn fill(&mut self) -> io::Result<()> {
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            self.eof = true;
+        }
+
+        let lexer = Lexer::new(&self.buffer);
+        let lexer = if self.in_module_header {
+            lexer
+        } else {
+            lexer.after_module_header()
+        };
+        let lexer = if self.buffer_offset == 0 {
+            lexer
+        } else {
+            lexer.after_first_line()
+        };
+        let tokens = lexer.collect::<Vec<_>>();
+
+        if !self.eof && tokens.last().is_some_and(is_incomplete) {
+            return Ok(());
+        }
+
+        self.in_module_header &= tokens
+            .iter()
+            .all(|token| matches!(token.raw, RawToken::ModuleDocComment(..)));
+
+        self.tokens.extend(tokens.into_iter().map(|token| Token {
+            location: Location {
+                start: self.buffer_offset + token.location.start,
+                end: self.buffer_offset + token.location.end,
+            },
+            ..token
+        }));
+
+        self.buffer_offset += self.buffer.len();
+        self.buffer.clear();
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for StreamingLexer<R> {
+    type Item = io::Result<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.tokens.pop_front() {
+                return Some(Ok(token));
+            }
+
+            if self.eof {
+                return None;
+            }
+
+            if let Err(error) = self.fill() {
+                return Some(Err(error));
+            }
+        }
+    }
+}
+
+/// Whether `token` could still grow into a valid token given more input.
+fn is_incomplete(token: &Token) -> bool {
+    matches!(
+        token.raw,
+        RawToken::Error(
+            LexError::UnterminatedString
+                | LexError::UnterminatedBlockComment
+                | LexError::UnterminatedChar
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{lexer::Lexer, token::RawToken};
+
+    use super::StreamingLexer;
+
+    #[test]
+    fn matches_in_memory_lexer() {
+        for source in [
+            "//! header\n\nvar greeting = \"hello\nworld\";\n//! not docs\n/// docs\nprint(greeting, 1.5);",
+            "a\n#!x b\nc",
+            "#!/usr/bin/env spectra\na\n#!x b\nc",
+        ] {
+            let streamed = StreamingLexer::new(Cursor::new(source.as_bytes().to_vec()))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            assert_eq!(streamed, Lexer::new(source).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn block_comment_across_lines() {
+        let source = "a /* one\n/* two */\nthree */ b\n/* unterminated\n";
+
+        let streamed = StreamingLexer::new(Cursor::new(source.as_bytes().to_vec()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(streamed, Lexer::new(source).collect::<Vec<_>>());
+        assert_eq!(streamed.len(), 3);
+    }
+
+    #[test]
+    fn newline_char_literal() {
+        let source = "var c = '\n';\n";
+
+        let streamed = StreamingLexer::new(Cursor::new(source.as_bytes().to_vec()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(streamed, Lexer::new(source).collect::<Vec<_>>());
+        assert_eq!(streamed[3].raw, RawToken::CharLiteral('\n'));
+    }
+
+    #[test]
+    fn unterminated_string_at_eof() {
+        let source = "a \"unterminated\nstring";
+
+        let streamed = StreamingLexer::new(Cursor::new(source.as_bytes().to_vec()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(streamed, Lexer::new(source).collect::<Vec<_>>());
+    }
+}
@@ -1,8 +1,10 @@
-use std::fmt;
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::{fmt, str::FromStr};
 
 use phf::phf_map;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
 pub struct Token {
     pub raw: RawToken,
     pub location: Location,
@@ -15,6 +17,7 @@ impl From<Token> for Precedence {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
 pub enum Keyword {
     Fun,
     Class,
@@ -25,6 +28,79 @@ pub enum Keyword {
     Break,
     Continue,
     Return,
+    Do,
+    Switch,
+    Case,
+    Default,
+    Pub,
+    Private,
+    Enum,
+    Yield,
+    Assert,
+    Typeof,
+    Sizeof,
+    Global,
+    Local,
+    Defer,
+    Trait,
+    New,
+}
+
+impl Keyword {
+    /// Looks `source` up in the same table the lexer uses.
+    #[must_use]
+    pub fn from_source(source: &str) -> Option<Self> {
+        match KEYWORDS.get(source) {
+            Some(RawToken::Keyword(keyword)) => Some(*keyword),
+            _ => None,
+        }
+    }
+
+    /// Keywords that can't begin an expression: ones that start a statement,
+    /// plus the `pub`/`private` modifiers in front of one and the `case` and
+    /// `default` arms of a `switch`.
+    #[must_use]
+    pub const fn is_statement_only(self) -> bool {
+        matches!(
+            self,
+            Self::Class
+                | Self::While
+                | Self::Var
+                | Self::Break
+                | Self::Continue
+                | Self::Return
+                | Self::Do
+                | Self::Switch
+                | Self::Enum
+                | Self::Yield
+                | Self::Assert
+                | Self::Global
+                | Self::Local
+                | Self::Defer
+                | Self::Trait
+                | Self::Pub
+                | Self::Private
+                | Self::Case
+                | Self::Default
+        )
+    }
+}
+
+impl FromStr for Keyword {
+    type Err = UnknownKeyword;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_source(s).ok_or_else(|| UnknownKeyword(s.to_owned()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKeyword(pub String);
+
+impl fmt::Display for UnknownKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a keyword", self.0)
+    }
 }
 
 impl fmt::Display for Keyword {
@@ -39,11 +115,28 @@ impl fmt::Display for Keyword {
             Self::Break => "`break`",
             Self::Continue => "`continue`",
             Self::Return => "`return`",
+            Self::Do => "`do`",
+            Self::Switch => "`switch`",
+            Self::Case => "`case`",
+            Self::Default => "`default`",
+            Self::Pub => "`pub`",
+            Self::Private => "`private`",
+            Self::Enum => "`enum`",
+            Self::Yield => "`yield`",
+            Self::Assert => "`assert`",
+            Self::Typeof => "`typeof`",
+            Self::Sizeof => "`sizeof`",
+            Self::Global => "`global`",
+            Self::Local => "`local`",
+            Self::Defer => "`defer`",
+            Self::Trait => "`trait`",
+            Self::New => "`new`",
         })
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "hash", derive(Eq, Hash))]
 pub enum Punctuation {
     Plus,
     PlusPlus,
@@ -56,6 +149,9 @@ pub enum Punctuation {
     StarEq,
     Slash,
     SlashEq,
+    QuestionQuestionEq,
+    AmpersandAmpersandEq,
+    PipePipeEq,
     OpenParent,
     CloseParent,
     OpenBracket,
@@ -65,7 +161,13 @@ pub enum Punctuation {
     Semicolon,
     Comma,
     Dot,
+    DotDot,
+    QuestionDot,
     Eq,
+    Caret,
+    Colon,
+    ColonColon,
+    Bang,
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -73,8 +175,10 @@ pub enum Precedence {
     #[default]
     Lowest,
     Assign,
+    BitwiseXor,
     Sum,
     Product,
+    Prefix,
     Power,
     Call,
     FieldAccess,
@@ -83,17 +187,24 @@ pub enum Precedence {
 impl From<Punctuation> for Precedence {
     fn from(value: Punctuation) -> Self {
         match value {
-            Punctuation::PlusEq
+            Punctuation::Eq
+            | Punctuation::PlusEq
             | Punctuation::MinusEq
             | Punctuation::StarEq
             | Punctuation::SlashEq
+            | Punctuation::QuestionQuestionEq
+            | Punctuation::AmpersandAmpersandEq
+            | Punctuation::PipePipeEq
             | Punctuation::PlusPlus
             | Punctuation::MinusMinus => Precedence::Assign,
+            Punctuation::Caret => Precedence::BitwiseXor,
             Punctuation::Plus | Punctuation::Minus => Precedence::Sum,
             Punctuation::Star | Punctuation::Slash => Precedence::Product,
             Punctuation::StarStar => Precedence::Power,
-            Punctuation::OpenParent => Precedence::Call,
-            Punctuation::Dot => Precedence::FieldAccess,
+            Punctuation::OpenParent | Punctuation::OpenBracket | Punctuation::Bang => {
+                Precedence::Call
+            }
+            Punctuation::Dot | Punctuation::QuestionDot => Precedence::FieldAccess,
             _ => Precedence::Lowest,
         }
     }
@@ -113,6 +224,9 @@ impl fmt::Display for Punctuation {
             Self::StarEq => "`*=`",
             Self::Slash => "`/`",
             Self::SlashEq => "`/=`",
+            Self::QuestionQuestionEq => "`??=`",
+            Self::AmpersandAmpersandEq => "`&&=`",
+            Self::PipePipeEq => "`||=`",
             Self::OpenParent => "`(`",
             Self::CloseParent => "`)`",
             Self::OpenBracket => "`[`",
@@ -122,22 +236,50 @@ impl fmt::Display for Punctuation {
             Self::Semicolon => "`;`",
             Self::Comma => "`,`",
             Self::Dot => "`.`",
+            Self::DotDot => "`..`",
+            Self::QuestionDot => "`?.`",
             Self::Eq => "`=`",
+            Self::Caret => "`^`",
+            Self::Colon => "`:`",
+            Self::ColonColon => "`::`",
+            Self::Bang => "`!`",
         })
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "hash"), derive(PartialEq))]
 pub enum RawToken {
     Identifier(String),
     StringLiteral(String),
+    /// `b"..."`, where `\x` escapes cover the whole byte range.
+    ByteStringLiteral(Vec<u8>),
     Keyword(Keyword),
     Punctuation(Punctuation),
     BoolLiteral(bool),
-    IntegerLiteral(u64),
-    FloatLiteral(f64),
+    /// Literals above [`u64::MAX`] lex as [`LexError::IntegerOverflow`]
+    /// instead. `source_text` keeps the literal exactly as written so later
+    /// stages can reinterpret it and diagnostics can show it in its original
+    /// radix.
+    IntegerLiteral {
+        value: u64,
+        source_text: String,
+    },
+    /// `source_text` keeps the literal as written, since `1.0` and `1e10`
+    /// don't survive a trip through `f64`'s own formatting.
+    FloatLiteral {
+        value: f64,
+        source_text: String,
+    },
     CharLiteral(char),
+    DocComment(String),
+    /// `//! text` at the very top of a file, before any other token.
+    ModuleDocComment(String),
+    /// `#if DEBUG` up to the end of the line, without the `#`. Only a `#`
+    /// at the start of a line, after any indentation, begins one.
+    Directive(String),
     UnexpectedChar(char),
+    Error(LexError),
 }
 
 impl fmt::Display for RawToken {
@@ -146,6 +288,7 @@ impl fmt::Display for RawToken {
             Self::Keyword(keyword) => keyword.fmt(f),
             Self::Identifier(name) => f.write_fmt(format_args!("identifier `{}`", name)),
             Self::StringLiteral(value) => value.fmt(f),
+            Self::ByteStringLiteral(value) => write!(f, "b\"{}\"", value.escape_ascii()),
             Self::Punctuation(punctuation) => punctuation.fmt(f),
             Self::BoolLiteral(value) => {
                 if *value {
@@ -154,10 +297,205 @@ impl fmt::Display for RawToken {
                     f.write_str("`false`")
                 }
             }
-            Self::IntegerLiteral(value) => value.fmt(f),
-            Self::FloatLiteral(value) => value.fmt(f),
+            Self::IntegerLiteral { source_text, .. } | Self::FloatLiteral { source_text, .. } => {
+                f.write_str(source_text)
+            }
             Self::CharLiteral(value) => f.write_fmt(format_args!("'{}'", value)),
+            Self::DocComment(..) => f.write_str("doc comment"),
+            Self::ModuleDocComment(..) => f.write_str("module doc comment"),
+            Self::Directive(directive) => f.write_fmt(format_args!("directive `#{}`", directive)),
             Self::UnexpectedChar(..) => f.write_str("invalid token"),
+            Self::Error(error) => error.fmt(f),
+        }
+    }
+}
+
+// With `hash`, float literals compare and hash by bit pattern, so `0.0` and
+// `-0.0` differ and NaN equals itself, as `Eq` requires.
+#[cfg(feature = "hash")]
+impl PartialEq for RawToken {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Identifier(a), Self::Identifier(b))
+            | (Self::StringLiteral(a), Self::StringLiteral(b))
+            | (Self::DocComment(a), Self::DocComment(b))
+            | (Self::ModuleDocComment(a), Self::ModuleDocComment(b))
+            | (Self::Directive(a), Self::Directive(b)) => a == b,
+            (Self::ByteStringLiteral(a), Self::ByteStringLiteral(b)) => a == b,
+            (Self::Keyword(a), Self::Keyword(b)) => a == b,
+            (Self::Punctuation(a), Self::Punctuation(b)) => a == b,
+            (Self::BoolLiteral(a), Self::BoolLiteral(b)) => a == b,
+            (
+                Self::IntegerLiteral {
+                    value: a,
+                    source_text: a_text,
+                },
+                Self::IntegerLiteral {
+                    value: b,
+                    source_text: b_text,
+                },
+            ) => a == b && a_text == b_text,
+            (
+                Self::FloatLiteral {
+                    value: a,
+                    source_text: a_text,
+                },
+                Self::FloatLiteral {
+                    value: b,
+                    source_text: b_text,
+                },
+            ) => a.to_bits() == b.to_bits() && a_text == b_text,
+            (Self::CharLiteral(a), Self::CharLiteral(b))
+            | (Self::UnexpectedChar(a), Self::UnexpectedChar(b)) => a == b,
+            (Self::Error(a), Self::Error(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Eq for RawToken {}
+
+#[cfg(feature = "hash")]
+impl core::hash::Hash for RawToken {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+
+        match self {
+            Self::Identifier(value)
+            | Self::StringLiteral(value)
+            | Self::DocComment(value)
+            | Self::ModuleDocComment(value)
+            | Self::Directive(value) => value.hash(state),
+            Self::ByteStringLiteral(value) => value.hash(state),
+            Self::Keyword(keyword) => keyword.hash(state),
+            Self::Punctuation(punctuation) => punctuation.hash(state),
+            Self::BoolLiteral(value) => value.hash(state),
+            Self::IntegerLiteral { value, source_text } => {
+                value.hash(state);
+                source_text.hash(state);
+            }
+            Self::FloatLiteral { value, source_text } => {
+                value.to_bits().hash(state);
+                source_text.hash(state);
+            }
+            Self::CharLiteral(value) | Self::UnexpectedChar(value) => value.hash(state),
+            Self::Error(error) => error.hash(state),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+pub enum LexError {
+    MultipleDecimalPoints,
+    TrailingCharactersAfterNumber,
+    /// A digit that isn't valid in the literal's radix, like the `2` in
+    /// `0b12`. The location spans just that digit.
+    InvalidDigit {
+        digit: char,
+        radix: u32,
+        location: Location,
+    },
+    /// `0x`, `0o` or `0b` with no digits after it.
+    MissingDigits,
+    /// An integer literal above [`u64::MAX`].
+    IntegerOverflow,
+    UnterminatedString,
+    /// `/*` without a matching `*/`, nested comments included.
+    UnterminatedBlockComment,
+    /// `'a` without the closing quote.
+    UnterminatedChar,
+    /// `'ab'`, more than one character between the quotes. The location
+    /// spans the whole literal.
+    MultipleCharsInChar,
+    /// `''`
+    EmptyChar,
+    InvalidEscape(EscapeError),
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MultipleDecimalPoints => f.write_str("invalid number: multiple decimal points"),
+            Self::TrailingCharactersAfterNumber => {
+                f.write_str("unexpected characters after number literal")
+            }
+            Self::InvalidDigit { digit, radix, .. } => write!(
+                f,
+                "invalid digit `{}` in {} literal",
+                digit,
+                match radix {
+                    2 => "binary",
+                    8 => "octal",
+                    _ => "hexadecimal",
+                }
+            ),
+            Self::MissingDigits => f.write_str("expected digits after the radix prefix"),
+            Self::IntegerOverflow => f.write_str("integer literal is too large"),
+            Self::UnterminatedString => f.write_str("unterminated string literal"),
+            Self::UnterminatedBlockComment => f.write_str("unterminated block comment"),
+            Self::UnterminatedChar => f.write_str("unterminated character literal"),
+            Self::MultipleCharsInChar => {
+                f.write_str("character literal may only contain one character")
+            }
+            Self::EmptyChar => f.write_str("empty character literal"),
+            Self::InvalidEscape(error) => error.fmt(f),
+        }
+    }
+}
+
+/// An escape sequence that doesn't decode to a character. The location
+/// spans the escape itself, from the backslash on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+pub struct EscapeError {
+    pub kind: EscapeErrorKind,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+pub enum EscapeErrorKind {
+    /// `\q`
+    Unknown(char),
+    /// `\x` not followed by two hex digits.
+    MalformedHex,
+    /// `\x80` and above; only ASCII can be written with `\x`.
+    NonAsciiHex(u32),
+    /// `\u` not followed by `{`, one to six hex digits and `}`.
+    MalformedUnicode,
+    /// `\u{D800}` through `\u{DFFF}`.
+    Surrogate(u32),
+    /// Anything above `\u{10FFFF}`.
+    OutOfRange(u32),
+}
+
+impl fmt::Display for EscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            EscapeErrorKind::Unknown(c) => write!(f, "unknown escape sequence `\\{}`", c),
+            EscapeErrorKind::MalformedHex => {
+                f.write_str("invalid escape: `\\x` must be followed by two hex digits")
+            }
+            EscapeErrorKind::NonAsciiHex(value) => write!(
+                f,
+                "invalid escape: `\\x{:02X}` is out of the ASCII range, use `\\u{{{:X}}}`",
+                value, value
+            ),
+            EscapeErrorKind::MalformedUnicode => {
+                f.write_str("invalid escape: expected `\\u{` followed by 1 to 6 hex digits and `}`")
+            }
+            EscapeErrorKind::Surrogate(value) => write!(
+                f,
+                "invalid escape: `\\u{{{:X}}}` is a surrogate, not a character",
+                value
+            ),
+            EscapeErrorKind::OutOfRange(value) => write!(
+                f,
+                "invalid escape: `\\u{{{:X}}}` is above the largest code point `10FFFF`",
+                value
+            ),
         }
     }
 }
@@ -168,6 +506,12 @@ impl From<Punctuation> for RawToken {
     }
 }
 
+impl From<Keyword> for RawToken {
+    fn from(value: Keyword) -> Self {
+        Self::Keyword(value)
+    }
+}
+
 impl From<RawToken> for Precedence {
     fn from(value: RawToken) -> Self {
         match value {
@@ -189,10 +533,109 @@ pub static KEYWORDS: phf::Map<&'static str, RawToken> = phf_map! {
     "break" => RawToken::Keyword(Keyword::Break),
     "continue" => RawToken::Keyword(Keyword::Continue),
     "return" => RawToken::Keyword(Keyword::Return),
+    "do" => RawToken::Keyword(Keyword::Do),
+    "switch" => RawToken::Keyword(Keyword::Switch),
+    "case" => RawToken::Keyword(Keyword::Case),
+    "default" => RawToken::Keyword(Keyword::Default),
+    "pub" => RawToken::Keyword(Keyword::Pub),
+    "private" => RawToken::Keyword(Keyword::Private),
+    "enum" => RawToken::Keyword(Keyword::Enum),
+    "yield" => RawToken::Keyword(Keyword::Yield),
+    "assert" => RawToken::Keyword(Keyword::Assert),
+    "typeof" => RawToken::Keyword(Keyword::Typeof),
+    "sizeof" => RawToken::Keyword(Keyword::Sizeof),
+    "global" => RawToken::Keyword(Keyword::Global),
+    "local" => RawToken::Keyword(Keyword::Local),
+    "defer" => RawToken::Keyword(Keyword::Defer),
+    "trait" => RawToken::Keyword(Keyword::Trait),
+    "new" => RawToken::Keyword(Keyword::New),
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
 pub struct Location {
     pub start: usize,
     pub end: usize,
 }
+
+impl Location {
+    /// Location of a node that doesn't come from the source at all.
+    pub const EMPTY: Self = Self::point(0);
+
+    /// A zero-width location at `offset`, for nodes synthesized while
+    /// desugaring something that starts there.
+    #[inline]
+    #[must_use]
+    pub const fn point(offset: usize) -> Self {
+        Self {
+            start: offset,
+            end: offset,
+        }
+    }
+
+    /// Whether this location was made with [`Location::EMPTY`] or
+    /// [`Location::point`]. Every token spans at least one byte, so real
+    /// locations are never zero-width.
+    #[inline]
+    #[must_use]
+    pub const fn is_synthetic(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{Expression, IdentifierAST},
+        lexer::Lexer,
+    };
+
+    use super::{Keyword, Location, Punctuation, RawToken, Token, UnknownKeyword};
+
+    #[test]
+    fn keyword_from_str() {
+        assert_eq!("fun".parse::<Keyword>(), Ok(Keyword::Fun));
+        assert_eq!(
+            "notakeyword".parse::<Keyword>(),
+            Err(UnknownKeyword("notakeyword".to_owned()))
+        );
+        assert_eq!(Keyword::from_source("true"), None);
+    }
+
+    #[test]
+    fn synthetic_locations() {
+        // `a += b` desugared into `a = a + b`
+        let a = Lexer::new("a += b").next().unwrap();
+        let desugared = Expression::Binary {
+            left: Box::new(Expression::Identifier(IdentifierAST {
+                identifier: "a".to_owned(),
+                location: Location::point(a.location.start),
+            })),
+            right: Box::new(Expression::Identifier(IdentifierAST {
+                identifier: "b".to_owned(),
+                location: Location { start: 5, end: 6 },
+            })),
+            operator: Token {
+                raw: Punctuation::Plus.into(),
+                location: Location::EMPTY,
+            },
+            location: Location::point(a.location.start),
+        };
+
+        assert!(!a.location.is_synthetic());
+        assert!(desugared.location().is_synthetic());
+        assert!(Location::EMPTY.is_synthetic());
+        assert_eq!(Location::EMPTY, Location::point(0));
+        assert!(!Location { start: 5, end: 6 }.is_synthetic());
+    }
+
+    #[test]
+    fn integer_display_keeps_source_text() {
+        let token = RawToken::IntegerLiteral {
+            value: 255,
+            source_text: "0xFF".to_owned(),
+        };
+
+        assert_eq!(token.to_string(), "0xFF");
+    }
+}
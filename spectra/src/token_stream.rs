@@ -0,0 +1,113 @@
+use crate::{lexer::Lexer, token::Token};
+use alloc::vec::Vec;
+
+/// Buffered tokens with arbitrary lookahead and backtracking.
+pub struct TokenStream {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
+impl TokenStream {
+    pub fn new(source: &str) -> Self {
+        Self::from(Lexer::new(source))
+    }
+
+    pub fn peek(&self) -> Option<&Token> {
+        self.peek_nth(0)
+    }
+
+    pub fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.position + n)
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.position)
+    }
+
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.position = checkpoint.0;
+    }
+}
+
+impl From<Lexer<'_>> for TokenStream {
+    fn from(lexer: Lexer<'_>) -> Self {
+        Self {
+            tokens: lexer.collect(),
+            position: 0,
+        }
+    }
+}
+
+impl Iterator for TokenStream {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.tokens.get(self.position).cloned();
+
+        if token.is_some() {
+            self.position += 1;
+        }
+
+        token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token::{Location, RawToken, Token};
+
+    use super::TokenStream;
+
+    #[test]
+    fn rewind() {
+        let mut stream = TokenStream::new("a b c");
+
+        assert_eq!(
+            stream.next().map(|token| token.raw),
+            Some(RawToken::Identifier("a".to_owned()))
+        );
+
+        let checkpoint = stream.checkpoint();
+
+        assert_eq!(
+            stream.next().map(|token| token.raw),
+            Some(RawToken::Identifier("b".to_owned()))
+        );
+        assert_eq!(
+            stream.next().map(|token| token.raw),
+            Some(RawToken::Identifier("c".to_owned()))
+        );
+        assert_eq!(stream.next(), None);
+
+        stream.rewind(checkpoint);
+
+        assert_eq!(
+            stream.next(),
+            Some(Token {
+                raw: RawToken::Identifier("b".to_owned()),
+                location: Location { start: 2, end: 3 }
+            })
+        );
+        assert_eq!(
+            stream.peek(),
+            Some(&Token {
+                raw: RawToken::Identifier("c".to_owned()),
+                location: Location { start: 4, end: 5 }
+            })
+        );
+    }
+
+    #[test]
+    fn peek_nth() {
+        let stream = TokenStream::new("a b");
+
+        assert_eq!(
+            stream.peek_nth(1).map(|token| &token.raw),
+            Some(&RawToken::Identifier("b".to_owned()))
+        );
+        assert_eq!(stream.peek_nth(2), None);
+    }
+}
@@ -0,0 +1,187 @@
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::{self, Display};
+
+use crate::{
+    ast::{Expression, Module, Statement, StatementsBlock},
+    token::Location,
+    visit::{self, Visitor},
+};
+
+/// A construct that parses fine but isn't allowed where it appears.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub message: String,
+    pub location: Location,
+    /// Another place the error refers to, such as the first declaration of
+    /// a duplicated name.
+    pub related: Option<Location>,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Checks placement rules the grammar doesn't enforce on its own:
+///
+/// - `yield` may only appear inside a function body.
+/// - `defer` may only appear inside a block, not directly at module level.
+/// - a function's parameters must have distinct names.
+#[must_use]
+pub fn validate(module: &Module) -> Vec<ValidationError> {
+    let mut validator = Validator {
+        function_depth: 0,
+        block_depth: 0,
+        errors: vec![],
+    };
+
+    for statement in &module.statements {
+        validator.visit_statement(statement);
+    }
+
+    validator.errors
+}
+
+struct Validator {
+    function_depth: usize,
+    /// Blocks entered, function bodies included.
+    block_depth: usize,
+    errors: Vec<ValidationError>,
+}
+
+impl Visitor for Validator {
+    fn visit_statements_block(&mut self, block: &StatementsBlock) {
+        self.block_depth += 1;
+        visit::walk_statements_block(self, block);
+        self.block_depth -= 1;
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        let restricted = match statement {
+            Statement::Yield { location, .. } if self.function_depth == 0 => {
+                Some(("`yield` outside of a function body", location))
+            }
+            Statement::Defer { location, .. } if self.block_depth == 0 => {
+                Some(("`defer` outside of a block", location))
+            }
+            _ => None,
+        };
+
+        if let Some((message, location)) = restricted {
+            self.errors.push(ValidationError {
+                message: message.into(),
+                location: *location,
+                related: None,
+            });
+        }
+
+        visit::walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        if let Expression::Function { parameters, .. } = expression {
+            for (index, parameter) in parameters.iter().enumerate() {
+                if let Some(first) = parameters[..index]
+                    .iter()
+                    .find(|previous| previous.identifier == parameter.identifier)
+                {
+                    self.errors.push(ValidationError {
+                        message: format!("duplicate parameter `{}`", parameter.identifier),
+                        location: parameter.location,
+                        related: Some(first.location),
+                    });
+                }
+            }
+
+            self.function_depth += 1;
+            visit::walk_expression(self, expression);
+            self.function_depth -= 1;
+        } else {
+            visit::walk_expression(self, expression);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parser::Parser, token::Location};
+
+    use super::{validate, ValidationError};
+
+    #[test]
+    fn yield_inside_function() {
+        let module = Parser::new("var gen = fun () { yield 1; };")
+            .parse()
+            .unwrap();
+
+        assert_eq!(validate(&module), vec![]);
+    }
+
+    #[test]
+    fn top_level_yield() {
+        let module = Parser::new("yield 1;").parse().unwrap();
+
+        assert_eq!(
+            validate(&module),
+            vec![ValidationError {
+                message: "`yield` outside of a function body".to_owned(),
+                location: Location { start: 0, end: 8 },
+                related: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn defer_inside_function() {
+        let module = Parser::new("var f = fun () { defer close(file); };")
+            .parse()
+            .unwrap();
+
+        assert_eq!(validate(&module), vec![]);
+    }
+
+    #[test]
+    fn top_level_defer() {
+        let module = Parser::new("defer close(file);").parse().unwrap();
+
+        assert_eq!(
+            validate(&module),
+            vec![ValidationError {
+                message: "`defer` outside of a block".to_owned(),
+                location: Location { start: 0, end: 18 },
+                related: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn defer_inside_block() {
+        let module = Parser::new("do { defer f(); } while x;").parse().unwrap();
+
+        assert_eq!(validate(&module), vec![]);
+    }
+
+    #[test]
+    fn distinct_parameters() {
+        let module = Parser::new("var f = fun (a, b) { a + b; };")
+            .parse()
+            .unwrap();
+
+        assert_eq!(validate(&module), vec![]);
+    }
+
+    #[test]
+    fn duplicate_parameters() {
+        let module = Parser::new("var f = fun (a, b, a) {};").parse().unwrap();
+
+        assert_eq!(
+            validate(&module),
+            vec![ValidationError {
+                message: "duplicate parameter `a`".to_owned(),
+                location: Location { start: 19, end: 20 },
+                related: Some(Location { start: 13, end: 14 }),
+            }]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::ast::{Expression, Statement, StatementsBlock};
+
+/// Read-only AST traversal. Every method defaults to walking into the
+/// node's children, so implementors only override what they care about.
+pub trait Visitor {
+    fn visit_statements_block(&mut self, block: &StatementsBlock) {
+        walk_statements_block(self, block);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+}
+
+pub fn walk_statements_block<V: Visitor + ?Sized>(visitor: &mut V, block: &StatementsBlock) {
+    for statement in &block.statements {
+        visitor.visit_statement(statement);
+    }
+
+    if let Some(tail) = &block.tail {
+        visitor.visit_expression(tail);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Expression { expression, .. } | Statement::Defer { expression, .. } => {
+            visitor.visit_expression(expression);
+        }
+        Statement::Return {
+            return_value: value,
+            ..
+        }
+        | Statement::Break { value, .. } => {
+            if let Some(value) = value {
+                visitor.visit_expression(value);
+            }
+        }
+        Statement::Continue { .. }
+        | Statement::Empty { .. }
+        | Statement::Enum { .. }
+        | Statement::Trait { .. } => {}
+        Statement::Var { value, .. } | Statement::Yield { value, .. } => {
+            visitor.visit_expression(value);
+        }
+        Statement::Assert {
+            condition, message, ..
+        } => {
+            visitor.visit_expression(condition);
+
+            if let Some(message) = message {
+                visitor.visit_expression(message);
+            }
+        }
+        Statement::DoWhile {
+            body, condition, ..
+        } => {
+            visitor.visit_statements_block(body);
+            visitor.visit_expression(condition);
+        }
+        Statement::Switch {
+            scrutinee,
+            cases,
+            default,
+            ..
+        } => {
+            visitor.visit_expression(scrutinee);
+
+            for case in cases {
+                visitor.visit_expression(&case.value);
+                visitor.visit_statements_block(&case.block);
+            }
+
+            if let Some(default) = default {
+                visitor.visit_statements_block(default);
+            }
+        }
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Literal(..) | Expression::Identifier(..) | Expression::Path { .. } => {}
+        Expression::Binary { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::Postfix { left, .. }
+        | Expression::FieldAccess { left, .. }
+        | Expression::OptionalFieldAccess { left, .. } => {
+            visitor.visit_expression(left);
+        }
+        Expression::Prefix { right, .. } => visitor.visit_expression(right),
+        Expression::Index { left, index, .. } | Expression::OptionalIndex { left, index, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(index);
+        }
+        Expression::Assignment { target, value, .. } => {
+            visitor.visit_expression(target);
+            visitor.visit_expression(value);
+        }
+        Expression::Call {
+            callee, arguments, ..
+        } => {
+            visitor.visit_expression(callee);
+
+            for argument in arguments {
+                visitor.visit_expression(&argument.value);
+            }
+        }
+        Expression::New { arguments, .. } => {
+            for argument in arguments {
+                visitor.visit_expression(&argument.value);
+            }
+        }
+        Expression::Grouping { inner, .. }
+        | Expression::ForceUnwrap {
+            expression: inner, ..
+        } => visitor.visit_expression(inner),
+        Expression::Function { block, .. } => visitor.visit_statements_block(block),
+        Expression::Comma { expressions, .. } => {
+            for expression in expressions {
+                visitor.visit_expression(expression);
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Compares allocation counts of the boxed AST and the arena. This lives in
+//! its own test binary because it replaces the global allocator.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use spectra::{arena::ArenaModule, parser::Parser};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn arena_allocates_less_than_boxes() {
+    let source = format!("{};", vec!["1"; 500].join(" + "));
+    let module = Parser::new(&source).parse().unwrap();
+
+    let (boxed, boxed_allocations) = count_allocations(|| module.clone());
+    let (arena, arena_allocations) = count_allocations(|| ArenaModule::from(&module));
+
+    assert_eq!(boxed, module);
+    assert_eq!(arena.expressions.len(), 999);
+    assert!(arena_allocations * 20 < boxed_allocations);
+}
@@ -0,0 +1,20 @@
+//! Uses the library from a `#![no_std]` crate. Run with
+//! `cargo test --no-default-features --test no_std` so that the library
+//! itself is also built without `std`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use spectra::{ast::Statement, lexer::Lexer, parser::Parser};
+
+#[test]
+fn lexes_and_parses_without_std() {
+    let tokens = Lexer::new("var a = 1;").collect::<Vec<_>>();
+    assert_eq!(tokens.len(), 5);
+
+    let module = Parser::new("var a = fun (b) { b * 2 };").parse().unwrap();
+    assert!(matches!(module.statements[..], [Statement::Var { .. }]));
+}